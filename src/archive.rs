@@ -0,0 +1,432 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::result::Result;
+use std::string::String;
+use std::time::Duration;
+use std::vec::Vec;
+use regex::Regex;
+use serde_json;
+use fetch::{self, Page, Resource};
+use html::{self, Tag, Token};
+use index::SearchIndex;
+use readability::Article;
+
+/// The most assets that are downloaded for one snapshot.
+const MAX_ASSETS: usize = 200;
+
+/// Information about an archived snapshot of a page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    pub url: String,
+    /// The URL the page was served from, after following redirects
+    pub fetched_url: String,
+    /// When the snapshot was taken, in seconds since the Unix epoch
+    pub archived: u64,
+}
+
+/// Archived page content, kept in a directory with a subdirectory for each link.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentStore {
+    root: PathBuf,
+}
+
+impl ContentStore {
+    pub fn new(root: &Path) -> ContentStore {
+        ContentStore { root: root.to_path_buf() }
+    }
+
+    /// Returns the content store kept next to a list file.
+    pub fn for_list_file(list_file_path: &str) -> ContentStore {
+        ContentStore::new(Path::new(&format!("{}.content", list_file_path)))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the directory holding a link's content.
+    pub fn entry_dir(&self, url: &str) -> PathBuf {
+        self.root.join(key(url))
+    }
+
+    pub fn snapshot_path(&self, url: &str) -> PathBuf {
+        self.entry_dir(url).join("snapshot.html")
+    }
+
+    fn snapshot_info_path(&self, url: &str) -> PathBuf {
+        self.entry_dir(url).join("snapshot.json")
+    }
+
+    pub fn snapshot(&self, url: &str) -> Option<String> {
+        fs::read_to_string(self.snapshot_path(url)).ok()
+    }
+
+    pub fn snapshot_info(&self, url: &str) -> Option<SnapshotInfo> {
+        fs::read_to_string(self.snapshot_info_path(url))
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+    }
+
+    /// Stores a snapshot of a link's page, replacing any earlier one, and returns its path.
+    pub fn save_snapshot(&self, info: &SnapshotInfo, document: &str) -> Result<PathBuf, String> {
+        fs::create_dir_all(self.entry_dir(&info.url)).map_err(|err| err.to_string())?;
+        let path = self.snapshot_path(&info.url);
+        fs::write(&path, document).map_err(|err| err.to_string())?;
+        let info_text = serde_json::to_string_pretty(info).map_err(|err| err.to_string())?;
+        fs::write(self.snapshot_info_path(&info.url), info_text).map_err(|err| err.to_string())?;
+        Ok(path)
+    }
+
+    fn article_path(&self, url: &str) -> PathBuf {
+        self.entry_dir(url).join("article.json")
+    }
+
+    /// Returns the readable article extracted from a link's page.
+    pub fn article(&self, url: &str) -> Option<Article> {
+        fs::read_to_string(self.article_path(url))
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+    }
+
+    /// Stores a link's readable article and indexes its text for searching.
+    pub fn save_article(&self, url: &str, article: &Article) -> Result<(), String> {
+        fs::create_dir_all(self.entry_dir(url)).map_err(|err| err.to_string())?;
+        let text = serde_json::to_string_pretty(article).map_err(|err| err.to_string())?;
+        fs::write(self.article_path(url), text).map_err(|err| err.to_string())?;
+        self.update_search_index(|index| index.add(url, &article.text))
+    }
+
+    fn search_index_path(&self) -> PathBuf {
+        self.root.join("index.json")
+    }
+
+    /// Returns the index of the stored articles' text, building it if it is missing.
+    pub fn search_index(&self) -> Result<SearchIndex, String> {
+        match fs::read_to_string(self.search_index_path()) {
+            Ok(text) => serde_json::from_str(&text).map_err(|err| err.to_string()),
+            Err(_) => self.rebuild_search_index(),
+        }
+    }
+
+    /// Indexes every stored article again, replacing the search index.
+    pub fn rebuild_search_index(&self) -> Result<SearchIndex, String> {
+        let mut index = SearchIndex::new();
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            // Nothing has been stored yet
+            Err(_) => return Ok(index),
+        };
+        for entry in entries {
+            let dir = entry.map_err(|err| err.to_string())?.path();
+            let info = fs::read_to_string(dir.join("snapshot.json"))
+                .ok()
+                .and_then(|text| serde_json::from_str::<SnapshotInfo>(&text).ok());
+            let article = fs::read_to_string(dir.join("article.json"))
+                .ok()
+                .and_then(|text| serde_json::from_str::<Article>(&text).ok());
+            if let (Some(info), Some(article)) = (info, article) {
+                index.add(&info.url, &article.text);
+            }
+        }
+        self.save_search_index(&index)?;
+        Ok(index)
+    }
+
+    fn save_search_index(&self, index: &SearchIndex) -> Result<(), String> {
+        fs::create_dir_all(&self.root).map_err(|err| err.to_string())?;
+        let text = serde_json::to_string(index).map_err(|err| err.to_string())?;
+        fs::write(self.search_index_path(), text).map_err(|err| err.to_string())
+    }
+
+    fn update_search_index<F>(&self, update: F) -> Result<(), String>
+    where
+        F: FnOnce(&mut SearchIndex),
+    {
+        let mut index = self.search_index()?;
+        update(&mut index);
+        self.save_search_index(&index)
+    }
+
+    /// Moves everything stored for a link to a new URL.
+    pub fn rename(&self, url: &str, new_url: &str) -> Result<(), String> {
+        let dir = self.entry_dir(url);
+        if !dir.exists() {
+            return Ok(());
+        }
+        self.delete(new_url)?;
+        fs::rename(dir, self.entry_dir(new_url)).map_err(|err| err.to_string())?;
+        if let Some(mut info) = self.snapshot_info(new_url) {
+            info.url = String::from(new_url);
+            let info_text = serde_json::to_string_pretty(&info).map_err(|err| err.to_string())?;
+            fs::write(self.snapshot_info_path(new_url), info_text)
+                .map_err(|err| err.to_string())?;
+        }
+        self.update_search_index(|index| index.rename(url, new_url))
+    }
+
+    /// Removes everything stored for a link.
+    pub fn delete(&self, url: &str) -> Result<(), String> {
+        let dir = self.entry_dir(url);
+        if !dir.exists() {
+            return Ok(());
+        }
+        fs::remove_dir_all(dir).map_err(|err| err.to_string())?;
+        self.update_search_index(|index| index.remove(url))
+    }
+}
+
+/// Returns the name of a link's directory in the content store (a 64-bit FNV-1a hash of its
+/// URL), which stays the same when the link's other fields change.
+pub fn key(url: &str) -> String {
+    let hash = url.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Downloads a page and the images, stylesheets and icons it uses, returning the URL it was
+/// served from and a self-contained HTML document.
+pub fn snapshot_page(url: &str, timeout: Duration) -> Result<(String, String), String> {
+    let page = fetch::fetch(url, timeout)?;
+    let document = snapshot_fetched_page(&page, timeout)?;
+    Ok((page.url, document))
+}
+
+/// Downloads the assets of a page that has already been fetched, returning a self-contained
+/// HTML document.
+pub fn snapshot_fetched_page(page: &Page, timeout: Duration) -> Result<String, String> {
+    if !page.is_html() {
+        return Err(format!("{} is not an HTML page ({})", page.url, page.content_type));
+    }
+    let mut cache: HashMap<String, Option<Resource>> = HashMap::new();
+    let document = inline_assets(&page.body, &page.url, |asset_url| {
+        if !cache.contains_key(asset_url) && cache.len() >= MAX_ASSETS {
+            return None;
+        }
+        cache
+            .entry(String::from(asset_url))
+            .or_insert_with(|| fetch::fetch_resource(asset_url, timeout).ok())
+            .clone()
+    });
+    Ok(document)
+}
+
+/// Rewrites an HTML document served from `base_url` so that it can be viewed offline.
+///
+/// Images, stylesheets, icons and the assets stylesheets refer to are looked up with
+/// `fetch_asset` and inlined as `data:` URLs; scripts are removed, and any other URL is made
+/// absolute so that it still works from the snapshot's location.
+pub fn inline_assets<F>(document: &str, base_url: &str, mut fetch_asset: F) -> String
+where
+    F: FnMut(&str) -> Option<Resource>,
+{
+    let tokens = html::tokenize(document);
+    let base_url = tokens
+        .iter()
+        .filter_map(|token| match *token {
+            Token::Start(ref tag) if tag.name == "base" => tag.attribute("href"),
+            _ => None,
+        })
+        .next()
+        .map(|href| fetch::resolve(base_url, href))
+        .unwrap_or_else(|| String::from(base_url));
+    let charset = Token::Start(Tag {
+        name: String::from("meta"),
+        attributes: vec![(String::from("charset"), String::from("utf-8"))],
+        self_closing: false,
+    });
+    let mut output = Vec::new();
+    let mut has_head = false;
+    let mut in_script = false;
+    let mut in_style = false;
+    for token in tokens {
+        if in_script {
+            in_script = token != Token::End(String::from("script"));
+            continue;
+        }
+        let mut tag = match token {
+            Token::Text(ref text) if in_style => {
+                output.push(Token::Text(inline_css(text, &base_url, &mut fetch_asset)));
+                continue;
+            }
+            Token::End(ref name) if name == "noscript" => continue,
+            Token::Start(tag) => tag,
+            token => {
+                in_style = false;
+                output.push(token);
+                continue;
+            }
+        };
+        in_style = false;
+        // Scripts won't run offline, and event handlers would refer to them
+        tag.attributes.retain(|attribute| {
+            !attribute.0.starts_with("on") && attribute.0 != "integrity" &&
+                attribute.0 != "nonce"
+        });
+        if let Some(style) = tag.attribute("style").map(String::from) {
+            set_attribute(&mut tag, "style", &inline_css(&style, &base_url, &mut fetch_asset));
+        }
+        match tag.name.as_ref() {
+            "script" => {
+                in_script = !tag.self_closing;
+                continue;
+            }
+            // With scripts removed, the fallback content applies
+            "noscript" | "base" | "source" => continue,
+            "meta" => {
+                let http_equiv = tag.attribute("http-equiv").unwrap_or("").to_lowercase();
+                if tag.attribute("charset").is_some() || http_equiv == "content-type" ||
+                    http_equiv == "refresh" || http_equiv == "content-security-policy"
+                {
+                    continue;
+                }
+            }
+            "head" => {
+                output.push(Token::Start(tag));
+                output.push(charset.clone());
+                has_head = true;
+                continue;
+            }
+            "style" => in_style = !tag.self_closing,
+            "link" => {
+                let rel = tag.attribute("rel").unwrap_or("").to_lowercase();
+                let href = tag.attribute("href").map(|href| fetch::resolve(&base_url, href));
+                let rels = rel.split_whitespace().collect::<Vec<&str>>();
+                if rels.iter().any(|rel| {
+                    ["preload", "prefetch", "modulepreload", "preconnect", "dns-prefetch"]
+                        .contains(rel)
+                })
+                {
+                    continue;
+                }
+                if let Some(href) = href {
+                    if rels.contains(&"stylesheet") {
+                        if let Some(stylesheet) = fetch_asset(&href) {
+                            let css = String::from_utf8_lossy(&stylesheet.bytes);
+                            let mut style = Tag {
+                                name: String::from("style"),
+                                attributes: Vec::new(),
+                                self_closing: false,
+                            };
+                            if let Some(media) = tag.attribute("media") {
+                                set_attribute(&mut style, "media", media);
+                            }
+                            output.push(Token::Start(style));
+                            output.push(Token::Text(
+                                inline_css(&css, &stylesheet.url, &mut fetch_asset),
+                            ));
+                            output.push(Token::End(String::from("style")));
+                            continue;
+                        }
+                        set_attribute(&mut tag, "href", &href);
+                    } else if rels.iter().any(|rel| rel.contains("icon")) {
+                        set_attribute(&mut tag, "href", &inline_url(&href, &mut fetch_asset));
+                    } else {
+                        set_attribute(&mut tag, "href", &href);
+                    }
+                }
+            }
+            "img" => {
+                // Lazy-loading pages keep the real image in a data attribute
+                let src = tag.attribute("data-src")
+                    .or_else(|| tag.attribute("src"))
+                    .map(|src| fetch::resolve(&base_url, src));
+                tag.attributes.retain(|attribute| {
+                    !["srcset", "sizes", "loading", "data-src", "data-srcset"]
+                        .contains(&attribute.0.as_ref())
+                });
+                if let Some(src) = src {
+                    set_attribute(&mut tag, "src", &inline_url(&src, &mut fetch_asset));
+                }
+            }
+            _ => {
+                for name in &["href", "src", "poster", "action"] {
+                    let value = tag.attribute(name)
+                        .filter(|value| !value.starts_with('#'))
+                        .map(|value| fetch::resolve(&base_url, value));
+                    if let Some(value) = value {
+                        set_attribute(&mut tag, name, &value);
+                    }
+                }
+            }
+        }
+        output.push(Token::Start(tag));
+    }
+    if !has_head {
+        output.insert(0, charset);
+    }
+    format!("<!DOCTYPE html>\n{}", html::serialize(&output))
+}
+
+fn set_attribute(tag: &mut Tag, name: &str, value: &str) {
+    match tag.attributes.iter().position(|attribute| attribute.0 == name) {
+        Some(index) => tag.attributes[index].1 = String::from(value),
+        None => tag.attributes.push((String::from(name), String::from(value))),
+    }
+}
+
+/// Replaces the `url()`s in a stylesheet with inlined assets.
+fn inline_css<F>(css: &str, base_url: &str, fetch_asset: &mut F) -> String
+where
+    F: FnMut(&str) -> Option<Resource>,
+{
+    lazy_static! {
+        static ref URL_RE: Regex = Regex::new(r#"url\(\s*(?:"([^"]*)"|'([^']*)'|([^)'"\s]*))\s*\)"#).unwrap();
+    }
+    URL_RE
+        .replace_all(css, |cap: &::regex::Captures| {
+            let url = cap.get(1)
+                .or_else(|| cap.get(2))
+                .or_else(|| cap.get(3))
+                .map(|url| url.as_str())
+                .unwrap_or("");
+            if url.is_empty() || url.starts_with('#') {
+                return String::from(&cap[0]);
+            }
+            let url = fetch::resolve(base_url, url);
+            format!("url(\"{}\")", inline_url(&url, fetch_asset))
+        })
+        .into_owned()
+}
+
+/// Returns a `data:` URL with the asset at `url`, or `url` itself if it can't be fetched.
+fn inline_url<F>(url: &str, fetch_asset: &mut F) -> String
+where
+    F: FnMut(&str) -> Option<Resource>,
+{
+    if url.starts_with("data:") {
+        return String::from(url);
+    }
+    match fetch_asset(url) {
+        None => String::from(url),
+        Some(asset) => {
+            let media_type = asset.content_type
+                .split(';')
+                .next()
+                .map(|media_type| media_type.trim())
+                .filter(|media_type| !media_type.is_empty())
+                .unwrap_or("application/octet-stream");
+            format!("data:{};base64,{}", media_type, base64(&asset.bytes))
+        }
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (index, byte)| group | u32::from(*byte) << (16 - 8 * index));
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * index) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
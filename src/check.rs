@@ -0,0 +1,220 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::string::String;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+use regex::Regex;
+use ureq;
+use fetch;
+
+/// How a link check turned out.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum Outcome {
+    Ok { status: u16 },
+    /// The URL redirects to a working page. The redirect is permanent only if every hop is.
+    Redirected { status: u16, to: String, permanent: bool },
+    HttpError { status: u16 },
+    DnsFailure,
+    Timeout,
+    ConnectionFailed { message: String },
+    TooManyRedirects,
+}
+
+impl Outcome {
+    pub fn is_broken(&self) -> bool {
+        !matches!(*self, Outcome::Ok { .. } | Outcome::Redirected { .. })
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Outcome::Ok { status } => write!(f, "OK ({})", status),
+            Outcome::Redirected {
+                ref to,
+                permanent,
+                ..
+            } => {
+                let kind = if permanent { "permanently" } else { "temporarily" };
+                write!(f, "redirects {} to {}", kind, to)
+            }
+            Outcome::HttpError { status } => write!(f, "HTTP error {}", status),
+            Outcome::DnsFailure => write!(f, "DNS lookup failed"),
+            Outcome::Timeout => write!(f, "timed out"),
+            Outcome::ConnectionFailed { ref message } => write!(f, "request failed: {}", message),
+            Outcome::TooManyRedirects => write!(f, "too many redirects"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CheckResult {
+    pub url: String,
+    #[serde(flatten)]
+    pub outcome: Outcome,
+}
+
+/// Settings for a link check.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckOptions {
+    /// How many links are checked at once
+    pub concurrency: usize,
+    /// The least time between two requests to the same host
+    pub host_interval: Duration,
+    pub timeout: Duration,
+    pub max_redirects: usize,
+}
+
+impl Default for CheckOptions {
+    fn default() -> CheckOptions {
+        CheckOptions {
+            concurrency: 8,
+            host_interval: Duration::from_millis(500),
+            timeout: Duration::from_secs(10),
+            max_redirects: 10,
+        }
+    }
+}
+
+/// Hands out request times so that requests to a host are spaced out.
+struct RateLimiter {
+    interval: Duration,
+    next_request: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateLimiter {
+    /// Blocks until a request may be made to the host of `url`.
+    fn wait(&self, url: &str) {
+        let now = Instant::now();
+        let slot = {
+            let mut next_request = self.next_request.lock().unwrap();
+            let next = next_request.entry(host(url)).or_insert(now);
+            let slot = if *next > now { *next } else { now };
+            *next = slot + self.interval;
+            slot
+        };
+        if slot > now {
+            thread::sleep(slot - now);
+        }
+    }
+}
+
+fn host(url: &str) -> String {
+    lazy_static! {
+        static ref HOST_RE: Regex = Regex::new(r"^[A-Za-z][A-Za-z0-9+.-]*://(?:[^/@]*@)?([^/?#]*)").unwrap();
+    }
+    HOST_RE
+        .captures(url)
+        .map(|cap| cap[1].to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Checks whether links still work, returning the results in the order of `urls`.
+pub fn check_all(urls: &[String], options: &CheckOptions) -> Vec<CheckResult> {
+    let queue = Arc::new(Mutex::new(
+        urls.iter().cloned().enumerate().collect::<VecDeque<(usize, String)>>(),
+    ));
+    let limiter = Arc::new(RateLimiter {
+        interval: options.host_interval,
+        next_request: Mutex::new(HashMap::new()),
+    });
+    let agent = fetch::agent(options.timeout, false);
+    let (sender, receiver) = mpsc::channel();
+    let workers = (0..options.concurrency.max(1).min(urls.len()))
+        .map(|_| {
+            let queue = queue.clone();
+            let limiter = limiter.clone();
+            let agent = agent.clone();
+            let sender = sender.clone();
+            let max_redirects = options.max_redirects;
+            thread::spawn(move || loop {
+                let next = queue.lock().unwrap().pop_front();
+                let (index, url) = match next {
+                    Some(next) => next,
+                    None => break,
+                };
+                let outcome = check_url(&agent, &limiter, &url, max_redirects);
+                sender.send((index, CheckResult { url, outcome })).unwrap();
+            })
+        })
+        .collect::<Vec<thread::JoinHandle<()>>>();
+    drop(sender);
+    let mut results = receiver.iter().collect::<Vec<(usize, CheckResult)>>();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    results.sort_by_key(|result| result.0);
+    results.into_iter().map(|result| result.1).collect()
+}
+
+fn check_url(
+    agent: &ureq::Agent,
+    limiter: &RateLimiter,
+    url: &str,
+    max_redirects: usize,
+) -> Outcome {
+    let mut current = String::from(url);
+    let mut visited = HashSet::new();
+    let mut permanent = true;
+    loop {
+        if !visited.insert(current.clone()) || visited.len() > max_redirects + 1 {
+            return Outcome::TooManyRedirects;
+        }
+        let response = match request(agent, limiter, &current) {
+            Ok(response) => response,
+            Err(outcome) => return outcome,
+        };
+        let status = response.status();
+        let location = response.header("location").map(String::from);
+        match (status, location) {
+            (300..=399, Some(location)) => {
+                permanent = permanent && (status == 301 || status == 308);
+                current = fetch::resolve(&current, &location);
+            }
+            (400..=599, _) => return Outcome::HttpError { status },
+            _ if current == url => return Outcome::Ok { status },
+            _ => {
+                return Outcome::Redirected {
+                    status,
+                    to: current,
+                    permanent,
+                }
+            }
+        }
+    }
+}
+
+/// Requests a URL with HEAD, falling back to GET for servers that don't handle HEAD properly.
+fn request(
+    agent: &ureq::Agent,
+    limiter: &RateLimiter,
+    url: &str,
+) -> Result<ureq::Response, Outcome> {
+    limiter.wait(url);
+    match agent.head(url).call() {
+        Ok(response) => return Ok(response),
+        Err(ureq::Error::Status(..)) => {}
+        Err(ureq::Error::Transport(transport)) => return Err(transport_outcome(&transport)),
+    }
+    limiter.wait(url);
+    match agent.get(url).call() {
+        Ok(response) => Ok(response),
+        Err(ureq::Error::Status(_, response)) => Ok(response),
+        Err(ureq::Error::Transport(transport)) => Err(transport_outcome(&transport)),
+    }
+}
+
+fn transport_outcome(transport: &ureq::Transport) -> Outcome {
+    let message = transport.to_string();
+    match transport.kind() {
+        ureq::ErrorKind::Dns => Outcome::DnsFailure,
+        // Timeouts surface as I/O errors, worded by the platform
+        _ if message.contains("timed out") || message.contains("temporarily unavailable") => {
+            Outcome::Timeout
+        }
+        _ => Outcome::ConnectionFailed { message },
+    }
+}
@@ -0,0 +1,327 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::result::Result;
+use std::string::String;
+use std::vec::Vec;
+use serde_json;
+use read_later_list::{LinkEntry, ReadLaterList};
+
+/// A Lamport timestamp, tie-broken by the ID of the replica that issued it.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Stamp {
+    pub counter: u64,
+    pub replica: String,
+}
+
+/// A last-writer-wins register holding a link entry's fields (everything except its tags).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LwwRegister {
+    pub value: LinkEntry,
+    pub stamp: Stamp,
+}
+
+impl LwwRegister {
+    fn merge(&mut self, other: &LwwRegister) {
+        let other_wins = match other.stamp.cmp(&self.stamp) {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            // Only possible when two devices share a replica ID; pick deterministically
+            Ordering::Equal => other.value.to_string() > self.value.to_string(),
+        };
+        if other_wins {
+            *self = other.clone();
+        }
+    }
+}
+
+/// An observed-remove set of strings.
+///
+/// Every add is recorded under a unique stamp; a remove tombstones the stamps it has observed,
+/// so an add that a remove has not seen survives a merge.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrSet {
+    adds: BTreeMap<String, BTreeSet<Stamp>>,
+    tombstones: BTreeSet<Stamp>,
+}
+
+impl OrSet {
+    pub fn new() -> OrSet {
+        OrSet {
+            adds: BTreeMap::new(),
+            tombstones: BTreeSet::new(),
+        }
+    }
+
+    fn live_stamps(&self, element: &str) -> Vec<&Stamp> {
+        match self.adds.get(element) {
+            None => Vec::new(),
+            Some(stamps) => stamps
+                .iter()
+                .filter(|stamp| !self.tombstones.contains(stamp))
+                .collect(),
+        }
+    }
+
+    pub fn contains(&self, element: &str) -> bool {
+        !self.live_stamps(element).is_empty()
+    }
+
+    /// Returns the live elements in the order they were first added.
+    pub fn elements(&self) -> Vec<String> {
+        let mut live = self.adds
+            .keys()
+            .filter_map(|element| {
+                self.live_stamps(element)
+                    .into_iter()
+                    .min()
+                    .map(|stamp| (stamp.clone(), element.clone()))
+            })
+            .collect::<Vec<(Stamp, String)>>();
+        live.sort();
+        live.into_iter().map(|(_, element)| element).collect()
+    }
+
+    pub fn add(&mut self, element: &str, stamp: Stamp) {
+        self.adds
+            .entry(String::from(element))
+            .or_default()
+            .insert(stamp);
+    }
+
+    pub fn remove(&mut self, element: &str) {
+        if let Some(stamps) = self.adds.get(element) {
+            for stamp in stamps {
+                self.tombstones.insert(stamp.clone());
+            }
+        }
+    }
+
+    fn add_stamps(&self) -> impl Iterator<Item = (&str, &Stamp)> {
+        self.adds
+            .iter()
+            .flat_map(|adds| adds.1.iter().map(move |stamp| (adds.0.as_str(), stamp)))
+    }
+
+    pub fn merge(&mut self, other: &OrSet) {
+        for (element, stamps) in &other.adds {
+            let own = self.adds
+                .entry(element.clone())
+                .or_default();
+            for stamp in stamps {
+                own.insert(stamp.clone());
+            }
+        }
+        for stamp in &other.tombstones {
+            self.tombstones.insert(stamp.clone());
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct EntryState {
+    fields: LwwRegister,
+    tags: OrSet,
+}
+
+/// Generates a replica ID that is very unlikely to be used by any other device.
+pub fn random_replica_id() -> String {
+    // Each `RandomState` is seeded with fresh random keys
+    let mut hasher = RandomState::new().build_hasher();
+    if let Ok(now) = SystemTime::now().duration_since(UNIX_EPOCH) {
+        hasher.write_u64(now.as_secs());
+        hasher.write_u32(now.subsec_nanos());
+    }
+    hasher.write_u32(process::id());
+    format!("{:016x}", hasher.finish())
+}
+
+/// A read-later list stored as a state-based CRDT.
+///
+/// The set of URLs is an observed-remove set, each entry's fields live in a
+/// last-writer-wins register, and each entry's tags form their own observed-remove set.
+/// Merging two replicas in any order converges to the same list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplicatedList {
+    replica: String,
+    clock: u64,
+    urls: OrSet,
+    entries: BTreeMap<String, EntryState>,
+}
+
+impl ReplicatedList {
+    pub fn new(replica: &str) -> ReplicatedList {
+        ReplicatedList {
+            replica: String::from(replica),
+            clock: 0,
+            urls: OrSet::new(),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Parses a replica from the JSON state produced by `to_state_string`.
+    pub fn parse(text: &str) -> Result<ReplicatedList, String> {
+        serde_json::from_str(text).map_err(|err| format!("Invalid replica state: {}", err))
+    }
+
+    pub fn to_state_string(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|err| err.to_string())
+    }
+
+    pub fn replica(&self) -> &str {
+        &self.replica
+    }
+
+    fn tick(&mut self) -> Stamp {
+        self.clock += 1;
+        Stamp {
+            counter: self.clock,
+            replica: self.replica.clone(),
+        }
+    }
+
+    pub fn get_link(&self, url: &str) -> Option<LinkEntry> {
+        if !self.urls.contains(url) {
+            return None;
+        }
+        self.entries.get(url).map(|state| {
+            let mut link = state.fields.value.clone();
+            link.tags = state.tags.elements();
+            link
+        })
+    }
+
+    /// Adds a link, or updates the fields and tags of an existing one.
+    ///
+    /// Any change adds the URL again, so a link edited on one replica survives being deleted
+    /// concurrently on another.
+    pub fn add_link(&mut self, link: LinkEntry) {
+        let mut fields = link.clone();
+        fields.tags = Vec::new();
+        let (fields_changed, tags_changed) = match self.entries.get(&link.url) {
+            None => (true, true),
+            Some(state) => {
+                let tags = state.tags.elements();
+                (
+                    state.fields.value != fields,
+                    tags.iter().any(|tag| !link.tags.contains(tag)) ||
+                        link.tags.iter().any(|tag| !tags.contains(tag)),
+                )
+            }
+        };
+        if !self.urls.contains(&link.url) || fields_changed || tags_changed {
+            let stamp = self.tick();
+            self.urls.add(&link.url, stamp);
+        }
+        if fields_changed {
+            let stamp = self.tick();
+            let register = LwwRegister {
+                value: fields,
+                stamp,
+            };
+            match self.entries.get_mut(&link.url) {
+                Some(state) => state.fields = register,
+                None => {
+                    self.entries.insert(
+                        link.url.clone(),
+                        EntryState {
+                            fields: register,
+                            tags: OrSet::new(),
+                        },
+                    );
+                }
+            }
+        }
+        let current_tags = self.entries[&link.url].tags.elements();
+        for tag in link.tags.iter().filter(|tag| !current_tags.contains(tag)) {
+            let stamp = self.tick();
+            self.entries.get_mut(&link.url).unwrap().tags.add(tag, stamp);
+        }
+        for tag in current_tags.iter().filter(|tag| !link.tags.contains(tag)) {
+            self.entries.get_mut(&link.url).unwrap().tags.remove(tag);
+        }
+    }
+
+    pub fn delete_link(&mut self, url: &str) {
+        self.urls.remove(url);
+        if let Some(state) = self.entries.get_mut(url) {
+            for tag in state.tags.elements() {
+                state.tags.remove(&tag);
+            }
+        }
+    }
+
+    /// Records the differences between this replica and `list` as local operations.
+    pub fn record(&mut self, list: &ReadLaterList) {
+        for link in list.iter_links() {
+            self.add_link(link.clone());
+        }
+        let deleted = self.to_read_later_list()
+            .iter_links()
+            .filter(|link| list.get_link(&link.url).is_none())
+            .map(|link| link.url.clone())
+            .collect::<Vec<String>>();
+        for url in deleted {
+            self.delete_link(&url);
+        }
+    }
+
+    /// Returns whether `other` has changes stamped with this replica's ID that this replica
+    /// never made, which means that another device uses the same ID.
+    pub fn conflicts_with(&self, other: &ReplicatedList) -> bool {
+        let own_adds = self.add_stamps().collect::<BTreeSet<(&str, Option<&str>, &Stamp)>>();
+        let foreign_add = other
+            .add_stamps()
+            .any(|add| add.2.replica == self.replica && !own_adds.contains(&add));
+        // Field registers are overwritten by merges, but this replica never issued a stamp
+        // past its own clock
+        let foreign_edit = other.entries.values().any(|state| {
+            state.fields.stamp.replica == self.replica && state.fields.stamp.counter > self.clock
+        });
+        foreign_add || foreign_edit
+    }
+
+    /// Returns every add to the set of URLs and to the sets of tags, which are never
+    /// discarded, as the URL, the tag if any, and the stamp.
+    fn add_stamps(&self) -> impl Iterator<Item = (&str, Option<&str>, &Stamp)> {
+        let url_adds = self.urls.add_stamps().map(|add| (add.0, None, add.1));
+        let tag_adds = self.entries.iter().flat_map(|entry| {
+            entry
+                .1
+                .tags
+                .add_stamps()
+                .map(move |add| (entry.0.as_str(), Some(add.0), add.1))
+        });
+        url_adds.chain(tag_adds)
+    }
+
+    /// Merges another replica's state into this one.
+    pub fn merge(&mut self, other: &ReplicatedList) {
+        self.clock = self.clock.max(other.clock);
+        self.urls.merge(&other.urls);
+        for (url, other_state) in &other.entries {
+            match self.entries.get_mut(url) {
+                Some(state) => {
+                    state.fields.merge(&other_state.fields);
+                    state.tags.merge(&other_state.tags);
+                }
+                None => {
+                    self.entries.insert(url.clone(), other_state.clone());
+                }
+            }
+        }
+    }
+
+    pub fn to_read_later_list(&self) -> ReadLaterList {
+        let mut list = ReadLaterList::new();
+        for url in self.urls.elements() {
+            if let Some(link) = self.get_link(&url) {
+                list.add_link(link);
+            }
+        }
+        list
+    }
+}
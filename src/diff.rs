@@ -0,0 +1,136 @@
+use std::string::String;
+use std::vec::Vec;
+
+/// The most pairs of lines compared when diffing; past this, changed text is shown as
+/// entirely removed and added rather than spending quadratic time and memory on it.
+const MAX_COMPARISONS: usize = 4_000_000;
+
+/// A line of a line-by-line comparison of two texts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+impl<'a> Line<'a> {
+    fn is_change(&self) -> bool {
+        !matches!(*self, Line::Same(_))
+    }
+}
+
+/// Compares two texts line by line, keeping the longest common subsequence of lines.
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<Line<'a>> {
+    let old_lines = old.lines().collect::<Vec<&str>>();
+    let new_lines = new.lines().collect::<Vec<&str>>();
+    let prefix = old_lines
+        .iter()
+        .zip(new_lines.iter())
+        .take_while(|lines| lines.0 == lines.1)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|lines| lines.0 == lines.1)
+        .count();
+    let old_middle = &old_lines[prefix..old_lines.len() - suffix];
+    let new_middle = &new_lines[prefix..new_lines.len() - suffix];
+
+    let mut lines = old_lines[..prefix]
+        .iter()
+        .map(|line| Line::Same(line))
+        .collect::<Vec<Line>>();
+    if old_middle.len() * new_middle.len() > MAX_COMPARISONS {
+        lines.extend(old_middle.iter().map(|line| Line::Removed(line)));
+        lines.extend(new_middle.iter().map(|line| Line::Added(line)));
+    } else {
+        lines.extend(common_subsequence_diff(old_middle, new_middle));
+    }
+    lines.extend(old_lines[old_lines.len() - suffix..].iter().map(|line| Line::Same(line)));
+    lines
+}
+
+fn common_subsequence_diff<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Line<'a>> {
+    // lengths[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(Line::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            lines.push(Line::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|line| Line::Removed(line)));
+    lines.extend(new[j..].iter().map(|line| Line::Added(line)));
+    lines
+}
+
+/// Renders the differences between two texts in unified diff format, with `context`
+/// unchanged lines around each change. Returns an empty string if the texts have the same
+/// lines.
+pub fn unified_diff(old: &str, new: &str, context: usize) -> String {
+    let lines = diff_lines(old, new);
+    // Where each hunk starts and ends in `lines`
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (index, _) in lines.iter().enumerate().filter(|line| line.1.is_change()) {
+        let start = index.saturating_sub(context);
+        let end = (index + context + 1).min(lines.len());
+        match hunks.last_mut() {
+            Some(hunk) if hunk.1 >= start => hunk.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+    let mut output = String::new();
+    let (mut old_line, mut new_line, mut position) = (0, 0, 0);
+    for hunk in hunks {
+        // Only unchanged lines come between hunks
+        old_line += hunk.0 - position;
+        new_line += hunk.0 - position;
+        let hunk_lines = &lines[hunk.0..hunk.1];
+        let old_count = hunk_lines
+            .iter()
+            .filter(|line| !matches!(**line, Line::Added(_)))
+            .count();
+        let new_count = hunk_lines
+            .iter()
+            .filter(|line| !matches!(**line, Line::Removed(_)))
+            .count();
+        // An empty range is numbered by the line before it
+        output.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            if old_count == 0 { old_line } else { old_line + 1 },
+            old_count,
+            if new_count == 0 { new_line } else { new_line + 1 },
+            new_count
+        ));
+        for line in hunk_lines {
+            match *line {
+                Line::Same(text) => output.push_str(&format!(" {}\n", text)),
+                Line::Removed(text) => output.push_str(&format!("-{}\n", text)),
+                Line::Added(text) => output.push_str(&format!("+{}\n", text)),
+            }
+        }
+        old_line += old_count;
+        new_line += new_count;
+        position = hunk.1;
+    }
+    output
+}
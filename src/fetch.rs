@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::result::Result;
+use std::string::String;
+use std::time::Duration;
+use regex::Regex;
+use serde_json::{self, Value};
+use ureq;
+use html::{self, Token};
+use util::collapse_whitespace;
+
+/// The most bytes of a response body that are read.
+const MAX_BODY_LENGTH: u64 = 10 * 1024 * 1024;
+/// How many redirects `expand_url` follows before giving up.
+const MAX_REDIRECTS: usize = 10;
+
+/// A fetched web page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    /// The URL the page was served from, after following redirects.
+    pub url: String,
+    pub content_type: String,
+    pub body: String,
+}
+
+impl Page {
+    pub fn is_html(&self) -> bool {
+        self.content_type.is_empty() || self.content_type.contains("html")
+    }
+}
+
+/// A fetched resource of any type, such as an image or stylesheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resource {
+    /// The URL the resource was served from, after following redirects.
+    pub url: String,
+    pub content_type: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Fetches a URL with a GET request, following redirects.
+pub fn fetch(url: &str, timeout: Duration) -> Result<Page, String> {
+    let resource = fetch_resource(url, timeout)?;
+    Ok(Page {
+        url: resource.url,
+        content_type: resource.content_type,
+        body: String::from_utf8_lossy(&resource.bytes).into_owned(),
+    })
+}
+
+/// Builds an HTTP client that gives up on a request after `timeout`. With `follow_redirects`
+/// unset, redirect responses are returned rather than followed.
+pub fn agent(timeout: Duration, follow_redirects: bool) -> ureq::Agent {
+    let builder = ureq::AgentBuilder::new()
+        .timeout(timeout)
+        .user_agent(&format!("open_read_later/{}", env!("CARGO_PKG_VERSION")));
+    if follow_redirects {
+        builder.build()
+    } else {
+        builder.redirects(0).build()
+    }
+}
+
+/// Follows the redirects from a URL, such as a short link, and returns the URL they lead to.
+pub fn expand_url(url: &str, timeout: Duration) -> Result<String, String> {
+    let agent = agent(timeout, false);
+    let mut visited = vec![String::from(url)];
+    loop {
+        let current = visited[visited.len() - 1].clone();
+        let response = match agent.get(&current).call() {
+            Ok(response) => response,
+            // Where a link leads is known even if the page there is an error
+            Err(ureq::Error::Status(_, response)) => response,
+            Err(ureq::Error::Transport(transport)) => {
+                return Err(format!("Could not fetch {}: {}", current, transport))
+            }
+        };
+        let next = match (response.status(), response.header("location")) {
+            (300..=399, Some(location)) => resolve(&current, location),
+            _ => return Ok(current),
+        };
+        if visited.contains(&next) {
+            return Err(format!("{} redirects in a loop", url));
+        }
+        if visited.len() > MAX_REDIRECTS {
+            return Err(format!("{} redirects more than {} times", url, MAX_REDIRECTS));
+        }
+        visited.push(next);
+    }
+}
+
+/// Fetches a URL with a GET request, following redirects, without decoding the response.
+pub fn fetch_resource(url: &str, timeout: Duration) -> Result<Resource, String> {
+    let agent = agent(timeout, true);
+    let response = agent.get(url).call().map_err(|err| match err {
+        ureq::Error::Status(status, _) => format!("Fetching {} failed with status {}", url, status),
+        ureq::Error::Transport(transport) => format!("Could not fetch {}: {}", url, transport),
+    })?;
+    let final_url = String::from(response.get_url());
+    let content_type = response
+        .header("content-type")
+        .unwrap_or("")
+        .to_lowercase();
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_BODY_LENGTH)
+        .read_to_end(&mut bytes)
+        .map_err(|err| format!("Could not read {}: {}", url, err))?;
+    Ok(Resource {
+        url: final_url,
+        content_type,
+        bytes,
+    })
+}
+
+/// Descriptive metadata about a page, gathered from its OpenGraph, Twitter card, JSON-LD and
+/// other `<meta>` tags.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub site_name: Option<String>,
+    pub published: Option<String>,
+    pub canonical_url: Option<String>,
+    pub language: Option<String>,
+}
+
+/// Extracts the metadata of a page fetched from `url`, leaving out a canonical URL that is
+/// the same as `url`.
+pub fn page_metadata(page: &Page, url: &str) -> Metadata {
+    if !page.is_html() {
+        return Metadata::default();
+    }
+    let mut metadata = extract_metadata(&page.body, &page.url);
+    if metadata.canonical_url.as_ref().map(|canonical| canonical == url) == Some(true) {
+        metadata.canonical_url = None;
+    }
+    metadata
+}
+
+/// Extracts a title from an HTML document, trying the `<title>` element, then the
+/// `og:title` meta tag, then the first `<h1>`.
+pub fn extract_title(document: &str) -> Option<String> {
+    let tokens = html::tokenize(document);
+    let mut title = None;
+    let mut og_title = None;
+    let mut heading = None;
+    for (index, token) in tokens.iter().enumerate() {
+        if let Token::Start(ref tag) = *token {
+            match tag.name.as_ref() {
+                "title" if title.is_none() => {
+                    title = non_empty(&html::text_until(&tokens, index + 1, "title").0);
+                }
+                "meta" if og_title.is_none() => {
+                    let property = tag.attribute("property").or_else(|| tag.attribute("name"));
+                    if property.map(|property| property.to_lowercase()) ==
+                        Some(String::from("og:title"))
+                    {
+                        og_title = tag.attribute("content").and_then(non_empty);
+                    }
+                }
+                "h1" if heading.is_none() => {
+                    heading = non_empty(&html::text_until(&tokens, index + 1, "h1").0);
+                }
+                _ => {}
+            }
+        }
+    }
+    title.or(og_title).or(heading)
+}
+
+fn non_empty(text: &str) -> Option<String> {
+    let text = collapse_whitespace(text);
+    if text.is_empty() {
+        None
+    } else {
+        Some(text)
+    }
+}
+
+/// Extracts metadata from an HTML document served from `base_url`, which is used to resolve a
+/// relative canonical URL.
+pub fn extract_metadata(document: &str, base_url: &str) -> Metadata {
+    let tokens = html::tokenize(document);
+    // Meta tag values by lowercased name or property, first occurrence winning
+    let mut meta: HashMap<String, String> = HashMap::new();
+    let mut canonical = None;
+    let mut language = None;
+    let mut linked_data = Vec::new();
+    for (index, token) in tokens.iter().enumerate() {
+        let tag = match *token {
+            Token::Start(ref tag) => tag,
+            _ => continue,
+        };
+        match tag.name.as_ref() {
+            "html" => language = tag.attribute("lang").and_then(non_empty),
+            "meta" => {
+                let key = tag.attribute("property")
+                    .or_else(|| tag.attribute("name"))
+                    .or_else(|| tag.attribute("http-equiv"));
+                let content = tag.attribute("content").and_then(non_empty);
+                if let (Some(key), Some(content)) = (key, content) {
+                    meta.entry(key.to_lowercase()).or_insert(content);
+                }
+            }
+            "link" if canonical.is_none() => {
+                let is_canonical = tag.attribute("rel")
+                    .map(|rel| {
+                        rel.split_whitespace()
+                            .any(|rel| rel.eq_ignore_ascii_case("canonical"))
+                    })
+                    .unwrap_or(false);
+                if is_canonical {
+                    canonical = tag.attribute("href").and_then(non_empty);
+                }
+            }
+            "script" => {
+                let is_linked_data = tag.attribute("type")
+                    .map(|kind| kind.trim().eq_ignore_ascii_case("application/ld+json"))
+                    .unwrap_or(false);
+                if is_linked_data {
+                    let text = html::text_until(&tokens, index + 1, "script").0;
+                    if let Ok(value) = serde_json::from_str::<Value>(&text) {
+                        linked_data_objects(value, &mut linked_data);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    let meta_value = |keys: &[&str]| {
+        keys.iter()
+            .filter_map(|key| meta.get(*key).cloned())
+            .next()
+    };
+    let linked_data_value = |key: &str| {
+        linked_data
+            .iter()
+            .filter_map(|object| object.get(key).and_then(linked_data_text))
+            .next()
+    };
+    let title = extract_title(document);
+    let description = meta_value(&["og:description", "twitter:description", "description"])
+        .or_else(|| linked_data_value("description"));
+    // article:author is often a profile URL rather than a name
+    let author = linked_data_value("author")
+        .or_else(|| {
+            meta_value(&["author", "article:author", "dc.creator", "citation_author"])
+                .filter(|author| !is_url(author))
+        })
+        .or_else(|| meta_value(&["twitter:creator"]));
+    let site_name = meta_value(&["og:site_name", "application-name"])
+        .or_else(|| linked_data_value("publisher"))
+        .or_else(|| meta_value(&["twitter:site"]));
+    let published = meta_value(&["article:published_time", "og:published_time"])
+        .or_else(|| linked_data_value("datePublished"))
+        .or_else(|| {
+            meta_value(&["date", "dc.date", "dcterms.created", "citation_publication_date"])
+        });
+    let canonical_url = canonical
+        .or_else(|| meta_value(&["og:url"]))
+        .map(|canonical| resolve(base_url, &canonical));
+    let language = language
+        .or_else(|| meta_value(&["content-language", "language", "dc.language"]))
+        .or_else(|| linked_data_value("inLanguage"))
+        .or_else(|| meta_value(&["og:locale"]).map(|locale| locale.replace("_", "-")));
+    Metadata {
+        title,
+        description,
+        author,
+        site_name,
+        published,
+        canonical_url,
+        language,
+    }
+}
+
+/// Collects the JSON objects in a JSON-LD document, including those in arrays and `@graph`s.
+fn linked_data_objects(value: Value, objects: &mut Vec<Value>) {
+    match value {
+        Value::Array(values) => {
+            for value in values {
+                linked_data_objects(value, objects);
+            }
+        }
+        Value::Object(mut object) => {
+            if let Some(graph) = object.remove("@graph") {
+                linked_data_objects(graph, objects);
+            }
+            objects.push(Value::Object(object));
+        }
+        _ => {}
+    }
+}
+
+/// Reads a JSON-LD property as text: strings are used directly, people and organizations by
+/// their name, and lists by their entries joined with commas.
+fn linked_data_text(value: &Value) -> Option<String> {
+    match *value {
+        Value::String(ref text) => non_empty(text),
+        Value::Object(ref object) => object.get("name").and_then(linked_data_text),
+        Value::Array(ref values) => {
+            let texts = values
+                .iter()
+                .filter_map(linked_data_text)
+                .collect::<Vec<String>>();
+            non_empty(&texts.join(", "))
+        }
+        _ => None,
+    }
+}
+
+fn is_url(text: &str) -> bool {
+    text.starts_with("http://") || text.starts_with("https://")
+}
+
+/// Resolves a possibly relative URL against the URL of the page it appeared on.
+pub fn resolve(base_url: &str, url: &str) -> String {
+    lazy_static! {
+        static ref SCHEME_RE: Regex = Regex::new(r"^[A-Za-z][A-Za-z0-9+.-]*:").unwrap();
+        static ref ORIGIN_RE: Regex = Regex::new(r"^([A-Za-z][A-Za-z0-9+.-]*:)//[^/?#]*").unwrap();
+    }
+    let url = url.trim();
+    if SCHEME_RE.is_match(url) {
+        return String::from(url);
+    }
+    let origin = match ORIGIN_RE.captures(base_url) {
+        Some(origin) => origin,
+        None => return String::from(url),
+    };
+    let base_without_fragment = &base_url[..base_url.find('#').unwrap_or(base_url.len())];
+    let base_path = &base_url[..base_url.find(['?', '#']).unwrap_or(base_url.len())];
+    let resolved = if url.starts_with("//") {
+        format!("{}{}", &origin[1], url)
+    } else if url.starts_with('/') {
+        format!("{}{}", &origin[0], url)
+    } else if url.starts_with('?') {
+        format!("{}{}", base_path, url)
+    } else if url.starts_with('#') || url.is_empty() {
+        format!("{}{}", base_without_fragment, url)
+    } else {
+        match base_path.rfind('/') {
+            Some(index) if index >= origin[0].len() => {
+                format!("{}{}", &base_path[..index + 1], url)
+            }
+            _ => format!("{}/{}", &origin[0], url),
+        }
+    };
+    remove_dot_segments(&resolved)
+}
+
+/// Removes `.` and `..` segments from the path of an absolute URL.
+fn remove_dot_segments(url: &str) -> String {
+    lazy_static! {
+        static ref PARTS_RE: Regex = Regex::new(r"^([A-Za-z][A-Za-z0-9+.-]*://[^/?#]*)([^?#]*)(.*)$").unwrap();
+    }
+    let parts = match PARTS_RE.captures(url) {
+        Some(parts) => parts,
+        None => return String::from(url),
+    };
+    let path = &parts[2];
+    if !path.split('/').any(|segment| segment == "." || segment == "..") {
+        return String::from(url);
+    }
+    let mut segments: Vec<&str> = Vec::new();
+    let mut ends_in_directory = false;
+    for segment in path.split('/').skip(1) {
+        ends_in_directory = segment == "." || segment == "..";
+        match segment {
+            "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    let mut path = format!("/{}", segments.join("/"));
+    if ends_in_directory && !path.ends_with('/') {
+        path.push('/');
+    }
+    format!("{}{}{}", &parts[1], path, &parts[3])
+}
@@ -0,0 +1,248 @@
+use std::collections::HashSet;
+use std::result::Result;
+use std::string::String;
+use std::vec::Vec;
+use chrono::{NaiveDate, TimeZone, Utc};
+use regex::Regex;
+use read_later_list::{LinkEntry, ReadLaterList};
+use util::collapse_whitespace;
+use super::{import_link, short_hash, slugify, sorted_links, ImportRecords};
+
+/// Renders a list as BibTeX. Links to DOIs become `@article` entries and everything else
+/// becomes `@misc`; the save date is written as `urldate`.
+pub fn render(list: &ReadLaterList) -> String {
+    sorted_links(list)
+        .iter()
+        .map(|link| render_entry(link))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Derives a citation key from a link's host, the first word of its title and a hash of its
+/// URL. The key depends on nothing but the link, so it stays the same from one export to the
+/// next, and the hash keeps links with the same host and title word apart.
+pub fn citation_key(link: &LinkEntry) -> String {
+    lazy_static! {
+        static ref HOST_RE: Regex = Regex::new(r"^[A-Za-z][A-Za-z0-9+.-]*://(?:[^/@]*@)?(?:www\.)?([^/:?#]+)").unwrap();
+    }
+    let host = HOST_RE
+        .captures(&link.url)
+        .map(|cap| {
+            let host = cap[1].to_lowercase();
+            let labels = host.split('.').collect::<Vec<&str>>();
+            // Skip the top-level domain where there is one
+            let label = if labels.len() > 1 { labels[labels.len() - 2] } else { labels[0] };
+            slugify(label).replace("-", "")
+        })
+        .unwrap_or_default();
+    let title = if link.title == link.url { "" } else { link.title.as_ref() };
+    let word = slugify(title)
+        .split('-')
+        .find(|word| word.len() > 3 && word.chars().all(|c| c.is_ascii_alphanumeric()))
+        .map(String::from)
+        .unwrap_or_default();
+    let hash = short_hash(&link.url);
+    [host.as_str(), word.as_str(), hash.as_str()]
+        .iter()
+        .filter(|part| !part.is_empty())
+        .cloned()
+        .collect::<Vec<&str>>()
+        .join("_")
+}
+
+fn render_entry(link: &LinkEntry) -> String {
+    let mut fields = vec![("title", format!("{{{}}}", escape(&link.title)))];
+    let doi = doi(&link.url);
+    if let Some(ref doi) = doi {
+        fields.push(("doi", format!("{{{}}}", doi)));
+    }
+    fields.push(("url", format!("{{{}}}", link.url)));
+    if let Some(added) = link.added {
+        if let Some(date) = Utc.timestamp_opt(added as i64, 0).single() {
+            fields.push(("urldate", format!("{{{}}}", date.format("%Y-%m-%d"))));
+        }
+    }
+    if !link.tags.is_empty() {
+        fields.push(("keywords", format!("{{{}}}", escape(&link.tags.join(", ")))));
+    }
+    format!(
+        "@{}{{{},\n{}\n}}\n",
+        if doi.is_some() { "article" } else { "misc" },
+        citation_key(link),
+        fields
+            .iter()
+            .map(|&(name, ref value)| format!("  {} = {}", name, value))
+            .collect::<Vec<String>>()
+            .join(",\n")
+    )
+}
+
+fn doi(url: &str) -> Option<String> {
+    lazy_static! {
+        static ref DOI_RE: Regex = Regex::new(r"^https?://(?:dx\.)?doi\.org/(10\.[^\s]+)$").unwrap();
+    }
+    DOI_RE.captures(url).map(|cap| String::from(&cap[1]))
+}
+
+/// Escapes the characters that LaTeX treats specially.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Reverses `escape` and strips the braces BibTeX uses to protect capitalization.
+fn unescape(text: &str) -> String {
+    text.replace("\\textbackslash{}", "\\")
+        .replace("\\textasciitilde{}", "~")
+        .replace("\\textasciicircum{}", "^")
+        .replace("\\{", "\u{0}")
+        .replace("\\}", "\u{1}")
+        .replace(['{', '}'], "")
+        .replace("\u{0}", "{")
+        .replace("\u{1}", "}")
+        .replace("\\&", "&")
+        .replace("\\%", "%")
+        .replace("\\$", "$")
+        .replace("\\#", "#")
+        .replace("\\_", "_")
+}
+
+/// Parses a BibTeX database. Each entry's `url`, or failing that its `doi`, becomes the link;
+/// `title` becomes its title and `keywords` its tags. Entries with neither field are skipped.
+pub fn parse(text: &str) -> Result<ImportRecords, String> {
+    lazy_static! {
+        static ref ENTRY_RE: Regex = Regex::new(r"@\s*([A-Za-z]+)\s*([\{(])").unwrap();
+    }
+    let mut links = Vec::new();
+    let mut skipped = Vec::new();
+    let mut position = 0;
+    while let Some(cap) = ENTRY_RE.captures(&text[position..]) {
+        let whole = cap.get(0).unwrap();
+        let kind = cap[1].to_lowercase();
+        let open = position + whole.end() - 1;
+        let body_start = open + 1;
+        let close = if &cap[2] == "(" { ')' } else { '}' };
+        let body_end = match delimited(&text[open..], close) {
+            Some(end) => open + end,
+            None => return Err(format!("Unterminated @{} entry", kind)),
+        };
+        position = body_end + 1;
+        if kind == "comment" || kind == "preamble" || kind == "string" {
+            continue;
+        }
+        let (key, fields) = parse_fields(&text[body_start..body_end]);
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|field| field.0 == name)
+                .map(|field| field.1.trim())
+        };
+        let url = match (field("url"), field("doi")) {
+            (Some(url), _) if !url.is_empty() => String::from(url),
+            (_, Some(doi)) if doi.starts_with("http") => String::from(doi),
+            (_, Some(doi)) if !doi.is_empty() => format!("https://doi.org/{}", doi),
+            _ => {
+                skipped.push(format!("{}: no url or doi", key));
+                continue;
+            }
+        };
+        let tags = field("keywords")
+            .map(|keywords| {
+                keywords
+                    .split([',', ';'])
+                    .map(unescape)
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default();
+        let added = field("urldate")
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|date| date.and_utc().timestamp() as u64);
+        let title = unescape(field("title").unwrap_or(""));
+        match import_link(&url, &title, &tags, added) {
+            Some(link) => links.push(link),
+            None => skipped.push(format!("{}: invalid entry", key)),
+        }
+    }
+    Ok(ImportRecords::new(links, skipped))
+}
+
+/// Splits an entry body into its citation key and lowercased field names with their values.
+fn parse_fields(body: &str) -> (String, Vec<(String, String)>) {
+    let mut parts = body.splitn(2, ',');
+    let key = String::from(parts.next().unwrap_or("").trim());
+    let mut rest = parts.next().unwrap_or("");
+    let mut fields = Vec::new();
+    let mut seen = HashSet::new();
+    while let Some(equals) = rest.find('=') {
+        let name = rest[..equals].trim().trim_start_matches(',').trim().to_lowercase();
+        let (value, remainder) = parse_value(&rest[equals + 1..]);
+        if seen.insert(name.clone()) {
+            fields.push((name, value));
+        }
+        rest = remainder;
+    }
+    (key, fields)
+}
+
+/// Parses a field value, which may concatenate braced, quoted and bare parts with `#`.
+fn parse_value(text: &str) -> (String, &str) {
+    let mut value = String::new();
+    let mut rest = text.trim_start();
+    loop {
+        let close = match rest.chars().next() {
+            Some('{') => Some('}'),
+            Some('"') => Some('"'),
+            _ => None,
+        };
+        let (part, remainder) = match close {
+            Some(close) => match delimited(rest, close) {
+                Some(end) => (&rest[1..end], &rest[end + 1..]),
+                None => (&rest[1..], ""),
+            },
+            None => {
+                let end = rest.find([',', '#']).unwrap_or(rest.len());
+                (rest[..end].trim(), &rest[end..])
+            }
+        };
+        value.push_str(part);
+        match remainder.trim_start().strip_prefix('#') {
+            Some(next) => rest = next.trim_start(),
+            None => {
+                rest = remainder.trim_start();
+                break;
+            }
+        }
+    }
+    (collapse_whitespace(&value), rest)
+}
+
+/// Finds the `close` character that ends the entry or value opened at the start of `text`,
+/// skipping over nested braces and escaped characters.
+fn delimited(text: &str, close: char) -> Option<usize> {
+    let mut depth = 0;
+    let mut escaped = false;
+    for (index, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == close && depth == 0 => return Some(index),
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
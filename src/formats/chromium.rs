@@ -0,0 +1,70 @@
+use std::result::Result;
+use std::string::String;
+use std::vec::Vec;
+use serde_json::{self, Value};
+use read_later_list::LinkEntry;
+use super::{import_link, ImportRecords};
+
+/// Seconds between the Windows epoch (1601-01-01), which Chromium uses, and the Unix epoch
+const WINDOWS_EPOCH_OFFSET: u64 = 11_644_473_600;
+
+/// Parses a Chromium `Bookmarks` file. The names of the folders a bookmark is filed under
+/// become its tags; the top-level bookmarks bar, other and mobile folders are ignored.
+pub fn parse(text: &str) -> Result<ImportRecords, String> {
+    let bookmarks: Value = serde_json::from_str(text)
+        .map_err(|err| format!("Not a Chromium bookmarks file: {}", err))?;
+    let roots = match bookmarks.get("roots").and_then(Value::as_object) {
+        Some(roots) => roots,
+        None => return Err(String::from("Not a Chromium bookmarks file: no roots")),
+    };
+    let mut links = Vec::new();
+    let mut skipped = Vec::new();
+    for root in roots.values() {
+        for child in children(root) {
+            collect(child, &mut Vec::new(), &mut links, &mut skipped);
+        }
+    }
+    Ok(ImportRecords::new(links, skipped))
+}
+
+fn children(node: &Value) -> &[Value] {
+    node.get("children")
+        .and_then(Value::as_array)
+        .map(|children| children.as_slice())
+        .unwrap_or(&[])
+}
+
+fn collect(
+    node: &Value,
+    folders: &mut Vec<String>,
+    links: &mut Vec<LinkEntry>,
+    skipped: &mut Vec<String>,
+) {
+    let name = node.get("name").and_then(Value::as_str).unwrap_or("");
+    match node.get("type").and_then(Value::as_str) {
+        Some("folder") => {
+            folders.push(String::from(name));
+            for child in children(node) {
+                collect(child, folders, links, skipped);
+            }
+            folders.pop();
+        }
+        Some("url") => {
+            let url = node.get("url").and_then(Value::as_str).unwrap_or("");
+            if url.starts_with("javascript:") || url.starts_with("chrome:") {
+                skipped.push(format!("{}: not a web page", url));
+                return;
+            }
+            // Chromium stores times as microseconds since the Windows epoch
+            let added = node.get("date_added")
+                .and_then(Value::as_str)
+                .and_then(|added| added.parse::<u64>().ok())
+                .and_then(|added| (added / 1_000_000).checked_sub(WINDOWS_EPOCH_OFFSET));
+            match import_link(url, name, folders, added) {
+                Some(link) => links.push(link),
+                None => skipped.push(format!("{}: invalid bookmark", name)),
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,231 @@
+use std::result::Result;
+use std::str::FromStr;
+use std::string::String;
+use std::vec::Vec;
+use csv_crate::{ReaderBuilder, WriterBuilder};
+use read_later_list::{self, LinkEntry, ReadLaterList};
+use util::collapse_whitespace;
+use super::{import_link, sorted_links};
+
+/// A link entry field that can be mapped to a column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Url,
+    Title,
+    Tags,
+    Added,
+    Read,
+    Description,
+    Author,
+    SiteName,
+    Published,
+    CanonicalUrl,
+    Language,
+    Aliases,
+    WordCount,
+    ReadingMinutes,
+}
+
+/// The fields that are exported when no columns are given.
+const DEFAULT_FIELDS: [Field; 5] =
+    [Field::Url, Field::Title, Field::Tags, Field::Added, Field::Read];
+
+const FIELDS: [Field; 14] = [
+    Field::Url,
+    Field::Title,
+    Field::Tags,
+    Field::Added,
+    Field::Read,
+    Field::Description,
+    Field::Author,
+    Field::SiteName,
+    Field::Published,
+    Field::CanonicalUrl,
+    Field::Language,
+    Field::Aliases,
+    Field::WordCount,
+    Field::ReadingMinutes,
+];
+
+impl Field {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Field::Url => "url",
+            Field::Title => "title",
+            Field::Tags => "tags",
+            Field::Added => "added",
+            Field::Read => "read",
+            Field::Description => "description",
+            Field::Author => "author",
+            Field::SiteName => "site_name",
+            Field::Published => "published",
+            Field::CanonicalUrl => "canonical_url",
+            Field::Language => "language",
+            Field::Aliases => "aliases",
+            Field::WordCount => "word_count",
+            Field::ReadingMinutes => "reading_minutes",
+        }
+    }
+
+    fn value(&self, link: &LinkEntry) -> String {
+        match *self {
+            Field::Url => link.url.clone(),
+            Field::Title => link.title.clone(),
+            Field::Tags => link.tags.join(", "),
+            Field::Added => link.added.map(|added| added.to_string()).unwrap_or_default(),
+            Field::Read => link.read.to_string(),
+            Field::Description => link.description.clone().unwrap_or_default(),
+            Field::Author => link.author.clone().unwrap_or_default(),
+            Field::SiteName => link.site_name.clone().unwrap_or_default(),
+            Field::Published => link.published.clone().unwrap_or_default(),
+            Field::CanonicalUrl => link.canonical_url.clone().unwrap_or_default(),
+            Field::Language => link.language.clone().unwrap_or_default(),
+            Field::Aliases => link.aliases.join(", "),
+            Field::WordCount => link.word_count.map(|count| count.to_string()).unwrap_or_default(),
+            Field::ReadingMinutes => {
+                link.reading_minutes.map(|minutes| minutes.to_string()).unwrap_or_default()
+            }
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Field, String> {
+        FIELDS
+            .iter()
+            .find(|field| field.name() == name.trim().to_lowercase())
+            .cloned()
+            .ok_or_else(|| format!("Unknown field {}", name))
+    }
+}
+
+/// Maps link entry fields to column headers, in column order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnMapping {
+    columns: Vec<(Field, String)>,
+}
+
+impl ColumnMapping {
+    /// Maps the url, title, tags, added and read fields to columns named after them.
+    pub fn new() -> ColumnMapping {
+        ColumnMapping {
+            columns: DEFAULT_FIELDS
+                .iter()
+                .map(|field| (*field, String::from(field.name())))
+                .collect(),
+        }
+    }
+
+    /// Parses column specs of the form `field` or `field=Header`, such as `url=Link`.
+    pub fn parse(specs: &[&str]) -> Result<ColumnMapping, String> {
+        let mut columns = Vec::new();
+        for spec in specs {
+            let mut parts = spec.splitn(2, '=');
+            let field = parts.next().unwrap_or("").parse::<Field>()?;
+            let header = match parts.next() {
+                None => String::from(field.name()),
+                Some(header) => String::from(header.trim()),
+            };
+            columns.push((field, header));
+        }
+        if !columns.iter().any(|&(field, _)| field == Field::Url) {
+            return Err(String::from("Column mapping must include the url field"));
+        }
+        Ok(ColumnMapping { columns })
+    }
+}
+
+impl Default for ColumnMapping {
+    fn default() -> ColumnMapping {
+        ColumnMapping::new()
+    }
+}
+
+/// Parses delimited text with a header row, such as CSV (`b','`) or TSV (`b'\t'`).
+///
+/// Mapped columns that are missing from the header are ignored, except for the url column.
+pub fn parse(text: &str, delimiter: u8, mapping: &ColumnMapping) -> Result<Vec<LinkEntry>, String> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let headers = reader.headers().map_err(|err| err.to_string())?.clone();
+    let indices = mapping
+        .columns
+        .iter()
+        .filter_map(|&(field, ref header)| {
+            headers
+                .iter()
+                .position(|column| column.trim().to_lowercase() == header.to_lowercase())
+                .map(|index| (field, index))
+        })
+        .collect::<Vec<(Field, usize)>>();
+    if !indices.iter().any(|&(field, _)| field == Field::Url) {
+        return Err(String::from("No url column found"));
+    }
+    let mut links = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|err| err.to_string())?;
+        let value = |wanted: Field| {
+            indices
+                .iter()
+                .find(|&&(field, _)| field == wanted)
+                .and_then(|&(_, index)| record.get(index))
+                .unwrap_or("")
+                .trim()
+        };
+        let tags = value(Field::Tags)
+            .split(',')
+            .map(String::from)
+            .collect::<Vec<String>>();
+        let added = value(Field::Added).parse().ok();
+        if let Some(mut link) = import_link(value(Field::Url), value(Field::Title), &tags, added) {
+            link.read = matches!(
+                value(Field::Read).to_lowercase().as_ref(),
+                "true" | "yes" | "1"
+            );
+            let text = |field: Field| match collapse_whitespace(value(field)) {
+                ref text if text.is_empty() => None,
+                text => Some(text),
+            };
+            link.description = text(Field::Description);
+            link.author = text(Field::Author);
+            link.site_name = text(Field::SiteName);
+            link.published = text(Field::Published);
+            link.canonical_url = text(Field::CanonicalUrl);
+            link.language = text(Field::Language);
+            link.aliases = value(Field::Aliases)
+                .split(',')
+                .map(|alias| alias.trim())
+                .filter(|alias| !alias.is_empty() && *alias != link.url)
+                .map(String::from)
+                .collect();
+            link.word_count = value(Field::WordCount).parse().ok();
+            link.reading_minutes = value(Field::ReadingMinutes)
+                .parse()
+                .ok()
+                .or_else(|| link.word_count.map(read_later_list::reading_minutes));
+            links.push(link);
+        }
+    }
+    Ok(links)
+}
+
+/// Renders a list as delimited text with a header row.
+pub fn render(list: &ReadLaterList, delimiter: u8, mapping: &ColumnMapping) -> Result<String, String> {
+    let mut writer = WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_writer(Vec::new());
+    writer
+        .write_record(mapping.columns.iter().map(|column| &column.1))
+        .map_err(|err| err.to_string())?;
+    for link in sorted_links(list) {
+        writer
+            .write_record(mapping.columns.iter().map(|&(field, _)| field.value(link)))
+            .map_err(|err| err.to_string())?;
+    }
+    let bytes = writer.into_inner().map_err(|err| err.to_string())?;
+    String::from_utf8(bytes).map_err(|err| err.to_string())
+}
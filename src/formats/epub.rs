@@ -0,0 +1,356 @@
+use std::collections::BTreeMap;
+use std::io::{Cursor, Write};
+use std::result::Result;
+use std::str::FromStr;
+use std::string::String;
+use std::vec::Vec;
+use zip::{CompressionMethod, ZipWriter};
+use zip::write::FileOptions;
+use html;
+use read_later_list::{LinkEntry, ReadLaterList};
+use super::{date_time, short_hash, sorted_links};
+
+/// How the chapters of an EPUB are ordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// Oldest first, with links that have no `added` date at the end.
+    Date,
+    /// Grouped under each link's first tag in alphabetical order, then by date.
+    Tag,
+}
+
+impl FromStr for Order {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Order, String> {
+        match name {
+            "date" => Ok(Order::Date),
+            "tag" => Ok(Order::Tag),
+            _ => Err(format!("Unknown chapter order {}", name)),
+        }
+    }
+}
+
+/// The heading for links without tags when ordering by tag.
+const UNTAGGED: &str = "Untagged";
+
+struct Chapter<'a> {
+    link: &'a LinkEntry,
+    path: String,
+}
+
+/// Renders a list as an EPUB 3 book with one chapter per link.
+///
+/// `content` looks up the archived HTML of a link; links without archived content get a
+/// chapter with just their title and URL. `modified` is the book's modification time in
+/// seconds since the Unix epoch.
+///
+/// Returns an error if the list has no links, as a book needs at least one chapter.
+pub fn render<F>(
+    list: &ReadLaterList,
+    title: &str,
+    order: Order,
+    modified: u64,
+    content: F,
+) -> Result<Vec<u8>, String>
+where
+    F: Fn(&LinkEntry) -> Option<String>,
+{
+    let sections = sections(list, order);
+    let chapters = sections
+        .iter()
+        .flat_map(|section| section.1.iter())
+        .enumerate()
+        .map(|(index, link)| {
+            Chapter {
+                link,
+                path: format!("chapters/{:04}.xhtml", index + 1),
+            }
+        })
+        .collect::<Vec<Chapter>>();
+    if chapters.is_empty() {
+        return Err(String::from("There are no links to put in the book"));
+    }
+    // Sections as runs of chapter indices, for the tables of contents
+    let mut start = 0;
+    let toc = sections
+        .iter()
+        .map(|section| {
+            let range = (start, start + section.1.len());
+            start = range.1;
+            (section.0.clone(), range)
+        })
+        .collect::<Vec<(Option<String>, (usize, usize))>>();
+    let urls = chapters
+        .iter()
+        .map(|chapter| chapter.link.url.as_ref())
+        .collect::<Vec<&str>>();
+    let identifier = format!("urn:open-read-later:{}", short_hash(&urls.join("\n")));
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    // The mimetype must come first and be uncompressed so readers can identify the file
+    add_file(&mut zip, "mimetype", "application/epub+zip", stored)?;
+    add_file(&mut zip, "META-INF/container.xml", CONTAINER, deflated)?;
+    add_file(
+        &mut zip,
+        "OEBPS/content.opf",
+        &render_package(title, &identifier, modified, &chapters),
+        deflated,
+    )?;
+    add_file(&mut zip, "OEBPS/nav.xhtml", &render_nav(title, &chapters, &toc), deflated)?;
+    add_file(
+        &mut zip,
+        "OEBPS/toc.ncx",
+        &render_ncx(title, &identifier, &chapters, &toc),
+        deflated,
+    )?;
+    for chapter in &chapters {
+        add_file(
+            &mut zip,
+            &format!("OEBPS/{}", chapter.path),
+            &render_chapter(chapter.link, content(chapter.link)),
+            deflated,
+        )?;
+    }
+    let cursor = zip.finish().map_err(|err| err.to_string())?;
+    Ok(cursor.into_inner())
+}
+
+fn add_file(
+    zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+    name: &str,
+    contents: &str,
+    options: FileOptions,
+) -> Result<(), String> {
+    zip.start_file(name, options).map_err(|err| err.to_string())?;
+    zip.write_all(contents.as_bytes()).map_err(|err| err.to_string())
+}
+
+/// Groups the links into titled sections in chapter order. Ordering by date gives a single
+/// untitled section.
+fn sections(list: &ReadLaterList, order: Order) -> Vec<(Option<String>, Vec<&LinkEntry>)> {
+    let mut links = sorted_links(list);
+    links.sort_by_key(|link| (link.added.is_none(), link.added));
+    match order {
+        Order::Date => vec![(None, links)],
+        Order::Tag => {
+            let mut sections: BTreeMap<(bool, String), Vec<&LinkEntry>> = BTreeMap::new();
+            for link in links {
+                let key = match link.tags.iter().min() {
+                    Some(tag) => (false, tag.clone()),
+                    None => (true, String::from(UNTAGGED)),
+                };
+                sections.entry(key).or_default().push(link);
+            }
+            sections
+                .into_iter()
+                .map(|(key, links)| (Some(key.1), links))
+                .collect()
+        }
+    }
+}
+
+const CONTAINER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<container version=\"1.0\" xmlns=\"urn:oasis:names:tc:opendocument:xmlns:container\">
+  <rootfiles>
+    <rootfile full-path=\"OEBPS/content.opf\" media-type=\"application/oebps-package+xml\"/>
+  </rootfiles>
+</container>
+";
+
+fn render_package(title: &str, identifier: &str, modified: u64, chapters: &[Chapter]) -> String {
+    let mut manifest = String::from(
+        "    <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>
+    <item id=\"ncx\" href=\"toc.ncx\" media-type=\"application/x-dtbncx+xml\"/>
+",
+    );
+    let mut spine = String::new();
+    for (index, chapter) in chapters.iter().enumerate() {
+        manifest.push_str(&format!(
+            "    <item id=\"chapter-{}\" href=\"{}\" media-type=\"application/xhtml+xml\"/>\n",
+            index + 1,
+            chapter.path
+        ));
+        spine.push_str(&format!("    <itemref idref=\"chapter-{}\"/>\n", index + 1));
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"book-id\">
+  <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">
+    <dc:identifier id=\"book-id\">{}</dc:identifier>
+    <dc:title>{}</dc:title>
+    <dc:language>en</dc:language>
+    <meta property=\"dcterms:modified\">{}</meta>
+  </metadata>
+  <manifest>
+{}  </manifest>
+  <spine toc=\"ncx\">
+{}  </spine>
+</package>
+",
+        html::escape(identifier),
+        html::escape(title),
+        date_time(modified).format("%Y-%m-%dT%H:%M:%SZ"),
+        manifest,
+        spine
+    )
+}
+
+fn render_nav(
+    title: &str,
+    chapters: &[Chapter],
+    toc: &[(Option<String>, (usize, usize))],
+) -> String {
+    let chapter_items = |range: (usize, usize), indent: &str| {
+        chapters[range.0..range.1]
+            .iter()
+            .map(|chapter| {
+                format!(
+                    "{}<li><a href=\"{}\">{}</a></li>\n",
+                    indent,
+                    chapter.path,
+                    html::escape(&chapter.link.title)
+                )
+            })
+            .collect::<String>()
+    };
+    let mut items = String::new();
+    for section in toc {
+        match section.0 {
+            None => items.push_str(&chapter_items(section.1, "        ")),
+            Some(ref heading) => {
+                items.push_str(&format!(
+                    "        <li><span>{}</span>\n          <ol>\n{}          </ol>\n        </li>\n",
+                    html::escape(heading),
+                    chapter_items(section.1, "            ")
+                ));
+            }
+        }
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<!DOCTYPE html>
+<html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" lang=\"en\" xml:lang=\"en\">
+  <head>
+    <title>{}</title>
+  </head>
+  <body>
+    <nav epub:type=\"toc\" id=\"toc\">
+      <h1>{}</h1>
+      <ol>
+{}      </ol>
+    </nav>
+  </body>
+</html>
+",
+        html::escape(title),
+        html::escape(title),
+        items
+    )
+}
+
+/// Renders the EPUB 2 table of contents, which older readers use instead of the nav document.
+fn render_ncx(
+    title: &str,
+    identifier: &str,
+    chapters: &[Chapter],
+    toc: &[(Option<String>, (usize, usize))],
+) -> String {
+    let nav_point = |id: String, label: &str, index: usize, children: &str, indent: &str| {
+        format!(
+            "{indent}<navPoint id=\"{}\" playOrder=\"{}\">
+{indent}  <navLabel><text>{}</text></navLabel>
+{indent}  <content src=\"{}\"/>
+{}{indent}</navPoint>
+",
+            id,
+            index + 1,
+            html::escape(label),
+            chapters[index].path,
+            children,
+            indent = indent
+        )
+    };
+    let chapter_points = |range: (usize, usize), indent: &str| {
+        (range.0..range.1)
+            .map(|index| {
+                nav_point(
+                    format!("chapter-{}", index + 1),
+                    &chapters[index].link.title,
+                    index,
+                    "",
+                    indent,
+                )
+            })
+            .collect::<String>()
+    };
+    let mut points = String::new();
+    for (section_index, section) in toc.iter().enumerate() {
+        match section.0 {
+            None => points.push_str(&chapter_points(section.1, "    ")),
+            // A section shares its play order with its first chapter, since both point at it
+            Some(ref heading) => points.push_str(&nav_point(
+                format!("section-{}", section_index + 1),
+                heading,
+                (section.1).0,
+                &chapter_points(section.1, "      "),
+                "    ",
+            )),
+        }
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<ncx xmlns=\"http://www.daisy.org/z3986/2005/ncx/\" version=\"2005-1\">
+  <head>
+    <meta name=\"dtb:uid\" content=\"{}\"/>
+  </head>
+  <docTitle><text>{}</text></docTitle>
+  <navMap>
+{}  </navMap>
+</ncx>
+",
+        html::escape(identifier),
+        html::escape(title),
+        points
+    )
+}
+
+fn render_chapter(link: &LinkEntry, content: Option<String>) -> String {
+    let mut details = Vec::new();
+    if let Some(added) = link.added {
+        details.push(format!("Saved {}", date_time(added).format("%Y-%m-%d")));
+    }
+    if !link.tags.is_empty() {
+        details.push(format!("Tagged {}", html::escape(&link.tags.join(", "))));
+    }
+    let mut body = format!(
+        "    <h1>{}</h1>\n    <p><a href=\"{}\">{}</a></p>\n",
+        html::escape(&link.title),
+        html::escape(&link.url),
+        html::escape(&link.url)
+    );
+    if !details.is_empty() {
+        body.push_str(&format!("    <p>{}</p>\n", details.join(" &#183; ")));
+    }
+    if let Some(content) = content {
+        body.push_str("    <hr/>\n");
+        body.push_str(&html::sanitize(&content));
+        body.push('\n');
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<!DOCTYPE html>
+<html xmlns=\"http://www.w3.org/1999/xhtml\" lang=\"en\" xml:lang=\"en\">
+  <head>
+    <title>{}</title>
+  </head>
+  <body>
+{}  </body>
+</html>
+",
+        html::escape(&link.title),
+        body
+    )
+}
@@ -0,0 +1,97 @@
+use std::string::String;
+use std::vec::Vec;
+use html;
+use read_later_list::{LinkEntry, ReadLaterList};
+use super::date_time;
+
+/// Renders a list as an Atom feed. `updated` is the feed's update time in seconds since the
+/// Unix epoch, which is also used for entries without an `added` date.
+pub fn render_atom(list: &ReadLaterList, title: &str, link: &str, updated: u64) -> String {
+    let mut output = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<feed xmlns=\"http://www.w3.org/2005/Atom\">
+  <title>{}</title>
+  <id>{}</id>
+  <link href=\"{}\"/>
+  <updated>{}</updated>
+  <author>
+    <name>{}</name>
+  </author>
+  <generator>open_read_later</generator>
+",
+        html::escape(title),
+        html::escape(link),
+        html::escape(link),
+        date_time(updated).to_rfc3339(),
+        html::escape(title)
+    );
+    for link in newest_first(list) {
+        output.push_str(&format!(
+            "  <entry>
+    <title>{}</title>
+    <id>{}</id>
+    <link href=\"{}\"/>
+    <updated>{}</updated>
+",
+            html::escape(&link.title),
+            html::escape(&link.url),
+            html::escape(&link.url),
+            date_time(link.added.unwrap_or(updated)).to_rfc3339()
+        ));
+        for tag in &link.tags {
+            output.push_str(&format!("    <category term=\"{}\"/>\n", html::escape(tag)));
+        }
+        output.push_str("  </entry>\n");
+    }
+    output.push_str("</feed>\n");
+    output
+}
+
+/// Renders a list as an RSS 2.0 feed. Entries without an `added` date get no `pubDate`.
+pub fn render_rss(list: &ReadLaterList, title: &str, link: &str, updated: u64) -> String {
+    let mut output = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<rss version=\"2.0\">
+  <channel>
+    <title>{}</title>
+    <link>{}</link>
+    <description>{}</description>
+    <lastBuildDate>{}</lastBuildDate>
+    <generator>open_read_later</generator>
+",
+        html::escape(title),
+        html::escape(link),
+        html::escape(title),
+        date_time(updated).to_rfc2822()
+    );
+    for link in newest_first(list) {
+        output.push_str(&format!(
+            "    <item>
+      <title>{}</title>
+      <link>{}</link>
+      <guid isPermaLink=\"true\">{}</guid>
+",
+            html::escape(&link.title),
+            html::escape(&link.url),
+            html::escape(&link.url)
+        ));
+        if let Some(added) = link.added {
+            output.push_str(&format!(
+                "      <pubDate>{}</pubDate>\n",
+                date_time(added).to_rfc2822()
+            ));
+        }
+        for tag in &link.tags {
+            output.push_str(&format!("      <category>{}</category>\n", html::escape(tag)));
+        }
+        output.push_str("    </item>\n");
+    }
+    output.push_str("  </channel>\n</rss>\n");
+    output
+}
+
+fn newest_first(list: &ReadLaterList) -> Vec<&LinkEntry> {
+    let mut links = list.iter_links().collect::<Vec<&LinkEntry>>();
+    links.sort_by(|a, b| b.added.cmp(&a.added).then_with(|| a.url.cmp(&b.url)));
+    links
+}
@@ -0,0 +1,162 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+use std::result::Result;
+use std::string::String;
+use std::vec::Vec;
+use rusqlite::{Connection, OpenFlags};
+use super::{import_link, ImportRecords};
+
+const TYPE_BOOKMARK: i64 = 1;
+const TAGS_ROOT: &str = "tags________";
+const ROOTS: [&str; 5] = [
+    "root________",
+    "menu________",
+    "toolbar_____",
+    "unfiled_____",
+    "mobile______",
+];
+
+struct Bookmark {
+    kind: i64,
+    parent: i64,
+    title: Option<String>,
+    guid: String,
+    added: Option<i64>,
+    url: Option<String>,
+    place_title: Option<String>,
+}
+
+/// Reads the bookmarks from a Firefox `places.sqlite` database.
+///
+/// The names of the folders a bookmark is filed under become its tags, along with its Firefox
+/// tags. Firefox keeps the database locked while it runs, so it is copied before being read.
+pub fn parse(places_path: &Path) -> Result<ImportRecords, String> {
+    let copy_path = env::temp_dir()
+        .join(format!("open_read_later-places-{}.sqlite", process::id()));
+    let wal_path = Path::new(&format!("{}-wal", places_path.display())).to_path_buf();
+    let copy_wal_path = Path::new(&format!("{}-wal", copy_path.display())).to_path_buf();
+    fs::copy(places_path, &copy_path)
+        .map_err(|err| format!("Cannot read {}: {}", places_path.display(), err))?;
+    if wal_path.exists() {
+        fs::copy(&wal_path, &copy_wal_path).map_err(|err| err.to_string())?;
+    }
+    let result = read_bookmarks(&copy_path);
+    let _ = fs::remove_file(&copy_path);
+    let _ = fs::remove_file(&copy_wal_path);
+    result
+}
+
+fn read_bookmarks(path: &Path) -> Result<ImportRecords, String> {
+    let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+        .map_err(|err| err.to_string())?;
+    let mut statement = connection
+        .prepare(
+            "SELECT b.id, b.type, b.parent, b.title, b.guid, b.dateAdded, p.url, p.title
+             FROM moz_bookmarks b LEFT JOIN moz_places p ON b.fk = p.id
+             ORDER BY b.parent, b.position",
+        )
+        .map_err(|err| format!("Not a Firefox places database: {}", err))?;
+    let rows = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                Bookmark {
+                    kind: row.get(1)?,
+                    parent: row.get::<_, Option<i64>>(2)?.unwrap_or(0),
+                    title: row.get(3)?,
+                    guid: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                    added: row.get(5)?,
+                    url: row.get(6)?,
+                    place_title: row.get(7)?,
+                },
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+    let mut ids = Vec::new();
+    let mut bookmarks = HashMap::new();
+    for row in rows {
+        let (id, bookmark) = row.map_err(|err| err.to_string())?;
+        ids.push(id);
+        bookmarks.insert(id, bookmark);
+    }
+
+    // Firefox stores tags as folders under the tags root containing a bookmark for each URL
+    let mut url_tags: HashMap<String, Vec<String>> = HashMap::new();
+    for id in &ids {
+        let bookmark = &bookmarks[id];
+        let tag_folder = match bookmarks.get(&bookmark.parent) {
+            Some(folder) if bookmark.kind == TYPE_BOOKMARK => folder,
+            _ => continue,
+        };
+        let is_tag = bookmarks
+            .get(&tag_folder.parent)
+            .map(|root| root.guid == TAGS_ROOT)
+            .unwrap_or(false);
+        if let (true, Some(url), Some(tag)) =
+            (is_tag, bookmark.url.as_ref(), tag_folder.title.as_ref())
+        {
+            url_tags.entry(url.clone()).or_default().push(tag.clone());
+        }
+    }
+
+    let mut links = Vec::new();
+    let mut skipped = Vec::new();
+    for id in &ids {
+        let bookmark = &bookmarks[id];
+        let url = match bookmark.url {
+            Some(ref url) if bookmark.kind == TYPE_BOOKMARK => url,
+            _ => continue,
+        };
+        let folders = match folder_names(&bookmarks, bookmark.parent) {
+            Some(folders) => folders,
+            // Tag assignments, which were collected above
+            None => continue,
+        };
+        if url.starts_with("place:") || url.starts_with("javascript:") {
+            skipped.push(format!("{}: not a web page", url));
+            continue;
+        }
+        let mut tags = folders;
+        tags.extend(url_tags.get(url).cloned().unwrap_or_default());
+        let title = bookmark.title
+            .as_ref()
+            .or(bookmark.place_title.as_ref())
+            .map(|title| title.as_ref())
+            .unwrap_or("");
+        // Firefox stores times in microseconds
+        let added = bookmark.added.map(|added| (added / 1_000_000) as u64);
+        match import_link(url, title, &tags, added) {
+            Some(link) => links.push(link),
+            None => skipped.push(format!("{}: invalid bookmark", url)),
+        }
+    }
+    Ok(ImportRecords::new(links, skipped))
+}
+
+/// Returns the names of the user folders enclosing a bookmark, outermost first, or `None` if
+/// the bookmark is under the tags root.
+fn folder_names(bookmarks: &HashMap<i64, Bookmark>, parent: i64) -> Option<Vec<String>> {
+    let mut names = Vec::new();
+    let mut current = parent;
+    // A corrupt database can have cycles of parents
+    let mut visited = HashSet::new();
+    while let Some(folder) = bookmarks.get(&current) {
+        if !visited.insert(current) {
+            break;
+        }
+        if folder.guid == TAGS_ROOT {
+            return None;
+        }
+        if !ROOTS.contains(&folder.guid.as_ref()) {
+            if let Some(ref title) = folder.title {
+                names.push(title.clone());
+            }
+        }
+        current = folder.parent;
+    }
+    names.reverse();
+    Some(names)
+}
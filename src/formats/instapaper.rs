@@ -0,0 +1,78 @@
+use std::result::Result;
+use std::string::String;
+use std::vec::Vec;
+use csv_crate::ReaderBuilder;
+use serde_json;
+use super::{import_link, ImportRecords};
+
+/// Parses Instapaper's CSV export, with its `URL`, `Title`, `Folder`, `Timestamp` and optional
+/// `Tags` columns.
+///
+/// Links in the Archive folder are marked as read. Starred links are tagged `starred`, and links
+/// in any other user folder are tagged with the folder name.
+pub fn parse(text: &str) -> Result<ImportRecords, String> {
+    let mut reader = ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let headers = reader.headers().map_err(|err| err.to_string())?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(name))
+    };
+    let url_column = column("URL").ok_or("Not an Instapaper export: no URL column")?;
+    let title_column = column("Title");
+    let folder_column = column("Folder");
+    let timestamp_column = column("Timestamp");
+    let tags_column = column("Tags");
+
+    let mut links = Vec::new();
+    let mut skipped = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        // Line 1 is the header
+        let line = index + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                skipped.push(format!("line {}: {}", line, err));
+                continue;
+            }
+        };
+        let value = |column: Option<usize>| {
+            column
+                .and_then(|column| record.get(column))
+                .unwrap_or("")
+                .trim()
+        };
+        let url = value(Some(url_column));
+        if url.is_empty() {
+            skipped.push(format!("line {}: no URL", line));
+            continue;
+        }
+        let mut tags = parse_tags(value(tags_column));
+        let folder = value(folder_column);
+        let read = folder.eq_ignore_ascii_case("archive");
+        if folder.eq_ignore_ascii_case("starred") {
+            tags.insert(0, String::from("starred"));
+        } else if !read && !folder.is_empty() && !folder.eq_ignore_ascii_case("unread") {
+            tags.insert(0, String::from(folder));
+        }
+        let added = value(timestamp_column).parse().ok();
+        match import_link(url, value(title_column), &tags, added) {
+            Some(mut link) => {
+                link.read = read;
+                links.push(link);
+            }
+            None => skipped.push(format!("line {}: invalid record", line)),
+        }
+    }
+    Ok(ImportRecords::new(links, skipped))
+}
+
+/// Newer exports write tags as a JSON array; accept a comma-separated list as well.
+fn parse_tags(text: &str) -> Vec<String> {
+    match serde_json::from_str::<Vec<String>>(text) {
+        Ok(tags) => tags,
+        Err(_) => text.split(',').map(String::from).collect(),
+    }
+}
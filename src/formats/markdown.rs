@@ -0,0 +1,125 @@
+use std::collections::BTreeSet;
+use std::result::Result;
+use std::string::String;
+use std::vec::Vec;
+use regex::Regex;
+use read_later_list::{LinkEntry, ReadLaterList};
+use super::{import_link, merge_duplicates, sorted_links};
+
+/// Parses links out of a Markdown document.
+///
+/// Every inline link or autolink with an absolute URL becomes a link entry. On bullet lines that
+/// start with a link, as written by `render`, the `#hashtags` after the link become its tags.
+pub fn parse(text: &str) -> Result<Vec<LinkEntry>, String> {
+    lazy_static! {
+        static ref LINK_RE: Regex = Regex::new(r#"(!?)\[((?:[^\[\]\\]|\\.)*)\]\(\s*(<[^<>\n]*>|[^\s()]+(?:\([^\s()]*\)[^\s()]*)*)(?:\s+"[^"]*")?\s*\)|<([A-Za-z][A-Za-z0-9+.-]*:[^<>\s]+)>"#).unwrap();
+        static ref BULLET_RE: Regex = Regex::new(r"^\s*(?:[-*+]|[0-9]+[.)])\s+$").unwrap();
+        static ref TAG_RE: Regex = Regex::new(r"(?:^|\s)#([^\s#]+)").unwrap();
+        static ref ABSOLUTE_URL_RE: Regex = Regex::new(r"^[A-Za-z][A-Za-z0-9+.-]*:").unwrap();
+        static ref ESCAPE_RE: Regex = Regex::new(r"\\([!-/:-@\[-`{-~])").unwrap();
+    }
+    let mut links = Vec::new();
+    let mut in_code_block = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        for cap in LINK_RE.captures_iter(line) {
+            let (title, url) = match cap.get(4) {
+                Some(url) => (url.as_str(), url.as_str()),
+                None => {
+                    if &cap[1] == "!" {
+                        continue;
+                    }
+                    let url = cap.get(3).unwrap().as_str();
+                    let url = url.trim_start_matches('<').trim_end_matches('>');
+                    (cap.get(2).unwrap().as_str(), url)
+                }
+            };
+            if !ABSOLUTE_URL_RE.is_match(url) {
+                continue;
+            }
+            let whole = cap.get(0).unwrap();
+            let tags = if BULLET_RE.is_match(&line[..whole.start()]) {
+                TAG_RE
+                    .captures_iter(&line[whole.end()..])
+                    .map(|tag| String::from(&tag[1]))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let title = ESCAPE_RE.replace_all(title, "$1");
+            if let Some(link) = import_link(url, &title, &tags, None) {
+                links.push(link);
+            }
+        }
+    }
+    Ok(merge_duplicates(links))
+}
+
+/// Renders a list as Markdown bullets of the form `- [title](url) #tag`.
+///
+/// When `group_by_tag` is set, the bullets are grouped under a heading for each tag, so links
+/// with several tags appear more than once.
+pub fn render(list: &ReadLaterList, group_by_tag: bool) -> String {
+    let links = sorted_links(list);
+    if !group_by_tag {
+        return links.iter().map(|link| render_link(link)).collect();
+    }
+    let tags = links
+        .iter()
+        .flat_map(|link| link.tags.iter())
+        .collect::<BTreeSet<&String>>();
+    let mut sections = Vec::new();
+    for tag in tags {
+        sections.push(format!(
+            "## {}\n\n{}",
+            tag,
+            links
+                .iter()
+                .filter(|link| link.tags.contains(tag))
+                .map(|link| render_link(link))
+                .collect::<String>()
+        ));
+    }
+    let untagged = links
+        .iter()
+        .filter(|link| link.tags.is_empty())
+        .map(|link| render_link(link))
+        .collect::<String>();
+    if !untagged.is_empty() {
+        sections.push(format!("## Untagged\n\n{}", untagged));
+    }
+    sections.join("\n")
+}
+
+fn render_link(link: &LinkEntry) -> String {
+    let url = if link.url.contains(|c: char| c.is_whitespace() || c == '(' || c == ')') {
+        format!("<{}>", link.url)
+    } else {
+        link.url.clone()
+    };
+    let mut output = format!("- [{}]({})", escape(&link.title), url);
+    for tag in &link.tags {
+        // Hashtags end at whitespace, so multi-word tags are hyphenated
+        let hashtag = tag.split_whitespace().collect::<Vec<&str>>().join("-");
+        output.push_str(&format!(" #{}", hashtag));
+    }
+    output.push('\n');
+    output
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == '[' || c == ']' || c == '\\' {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
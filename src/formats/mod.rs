@@ -0,0 +1,234 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::string::String;
+use std::vec::Vec;
+use chrono::{DateTime, TimeZone, Utc};
+use read_later_list::{LinkEntry, ReadLaterList};
+use util::collapse_whitespace;
+
+pub mod bibtex;
+pub mod chromium;
+pub mod csv;
+pub mod epub;
+pub mod feed;
+pub mod firefox;
+pub mod instapaper;
+pub mod markdown;
+pub mod netscape;
+pub mod opml;
+pub mod org;
+pub mod pinboard;
+pub mod pocket;
+pub mod site;
+pub mod todotxt;
+pub mod vault;
+
+/// The links read from an export, along with the records that could not be imported and the
+/// URLs of records that were merged into an earlier record for the same URL.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRecords {
+    pub links: Vec<LinkEntry>,
+    pub skipped: Vec<String>,
+    pub merged: Vec<String>,
+}
+
+impl ImportRecords {
+    /// Merges duplicate links, noting each merged URL.
+    fn new(links: Vec<LinkEntry>, skipped: Vec<String>) -> ImportRecords {
+        let mut merged = Vec::new();
+        let links = merge_links(links, &mut merged);
+        ImportRecords {
+            links,
+            skipped,
+            merged,
+        }
+    }
+}
+
+impl ImportRecords {
+    /// Folds links that are already in `list` into the existing entries, keeping their titles
+    /// and other fields and adding any new tags.
+    pub fn merge_into_existing(&mut self, list: &ReadLaterList) {
+        for link in &mut self.links {
+            if let Some(existing) = list.get_link(&link.url) {
+                let mut merged = existing.clone();
+                for tag in &link.tags {
+                    if !merged.tags.contains(tag) {
+                        merged.tags.push(tag.clone());
+                    }
+                }
+                *link = merged;
+            }
+        }
+    }
+}
+
+impl From<Vec<LinkEntry>> for ImportRecords {
+    fn from(links: Vec<LinkEntry>) -> ImportRecords {
+        ImportRecords {
+            links,
+            skipped: Vec::new(),
+            merged: Vec::new(),
+        }
+    }
+}
+
+/// Describes how a batch of imported links relates to the list it is imported into.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImportSummary {
+    pub new: Vec<String>,
+    pub updated: Vec<String>,
+    pub unchanged: Vec<String>,
+    pub merged: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+impl ImportSummary {
+    /// Compares `links` against `list`. Call this before adding the links to the list.
+    pub fn new(list: &ReadLaterList, links: &[LinkEntry]) -> ImportSummary {
+        let mut summary = ImportSummary {
+            new: Vec::new(),
+            updated: Vec::new(),
+            unchanged: Vec::new(),
+            merged: Vec::new(),
+            skipped: Vec::new(),
+        };
+        let mut seen = HashSet::new();
+        for link in links.iter().rev() {
+            if !seen.insert(&link.url) {
+                continue;
+            }
+            match list.get_link(&link.url) {
+                None => summary.new.push(link.url.clone()),
+                Some(existing) if existing == link => summary.unchanged.push(link.url.clone()),
+                Some(_) => summary.updated.push(link.url.clone()),
+            }
+        }
+        summary.new.reverse();
+        summary.updated.reverse();
+        summary.unchanged.reverse();
+        summary
+    }
+
+    /// Like `new`, but also reports the records that were merged or skipped while parsing.
+    pub fn from_records(list: &ReadLaterList, records: &ImportRecords) -> ImportSummary {
+        let mut summary = ImportSummary::new(list, &records.links);
+        summary.merged = records.merged.clone();
+        summary.skipped = records.skipped.clone();
+        summary
+    }
+}
+
+impl fmt::Display for ImportSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} new, {} updated, {} unchanged",
+            self.new.len(),
+            self.updated.len(),
+            self.unchanged.len()
+        )?;
+        if !self.merged.is_empty() {
+            write!(f, ", {} merged", self.merged.len())?;
+        }
+        if !self.skipped.is_empty() {
+            write!(f, ", {} skipped", self.skipped.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the list's links in a stable order for exporting.
+fn sorted_links(list: &ReadLaterList) -> Vec<&LinkEntry> {
+    let mut links = list.iter_links().collect::<Vec<&LinkEntry>>();
+    links.sort_by(|a, b| a.url.cmp(&b.url));
+    links
+}
+
+/// Merges links that share a URL into the first of them, combining their tags. A title that is
+/// just the URL is replaced by a real one if a later duplicate has it.
+fn merge_duplicates(links: Vec<LinkEntry>) -> Vec<LinkEntry> {
+    merge_links(links, &mut Vec::new())
+}
+
+fn merge_links(links: Vec<LinkEntry>, merged_urls: &mut Vec<String>) -> Vec<LinkEntry> {
+    let mut merged: Vec<LinkEntry> = Vec::new();
+    let mut indices: HashMap<String, usize> = HashMap::new();
+    for link in links {
+        match indices.get(&link.url) {
+            Some(&index) => {
+                merged_urls.push(link.url.clone());
+                let existing = &mut merged[index];
+                if existing.title == existing.url {
+                    existing.title = link.title;
+                }
+                if existing.added.is_none() {
+                    existing.added = link.added;
+                }
+                for tag in link.tags {
+                    if !existing.tags.contains(&tag) {
+                        existing.tags.push(tag);
+                    }
+                }
+                continue;
+            }
+            None => {
+                indices.insert(link.url.clone(), merged.len());
+            }
+        }
+        merged.push(link);
+    }
+    merged
+}
+
+/// Converts text into a lowercase, hyphen-separated string that is safe to use in file names.
+fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+/// Returns a short, stable hexadecimal hash of `text` (32-bit FNV-1a).
+fn short_hash(text: &str) -> String {
+    let hash = text.bytes().fold(0x811c_9dc5u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+    format!("{:08x}", hash)
+}
+
+/// Converts seconds since the Unix epoch to a UTC date and time, falling back to the epoch
+/// for timestamps out of range.
+pub(crate) fn date_time(timestamp: u64) -> DateTime<Utc> {
+    Utc.timestamp_opt(timestamp as i64, 0)
+        .single()
+        .unwrap_or_else(|| Utc.timestamp_opt(0, 0).unwrap())
+}
+
+/// Builds a link entry from imported fields, falling back to the URL when there is no title.
+fn import_link(url: &str, title: &str, tags: &[String], added: Option<u64>) -> Option<LinkEntry> {
+    let url = url.trim();
+    if url.is_empty() {
+        return None;
+    }
+    let title = match collapse_whitespace(title).as_ref() {
+        "" => String::from(url),
+        title => String::from(title),
+    };
+    let mut tag_vals: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = collapse_whitespace(&tag.replace(",", " "));
+        if !tag.is_empty() && !tag_vals.contains(&tag) {
+            tag_vals.push(tag);
+        }
+    }
+    let mut builder = LinkEntry::builder()
+        .set_url(url)
+        .set_title(&title)
+        .add_tags(&mut tag_vals.iter().map(|tag| tag.as_ref()).collect());
+    if let Some(added) = added {
+        builder = builder.set_added(added);
+    }
+    builder.build().ok()
+}
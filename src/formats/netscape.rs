@@ -0,0 +1,127 @@
+use std::result::Result;
+use std::string::String;
+use std::vec::Vec;
+use html::{self, Tag, Token};
+use read_later_list::{LinkEntry, ReadLaterList};
+use super::{import_link, sorted_links};
+
+/// Browsers omit closing tags freely, so text ends at the next of these elements.
+const STRUCTURAL_ELEMENTS: &[&str] = &["dt", "dl"];
+
+/// Parses a Netscape bookmark file. Each folder an entry is nested in becomes one of its tags,
+/// alongside any tags listed in its `TAGS` attribute.
+pub fn parse(text: &str) -> Result<Vec<LinkEntry>, String> {
+    let tokens = html::tokenize(text);
+    let mut links = Vec::new();
+    // One element per open <DL>, holding the folder name it belongs to, if any
+    let mut folders: Vec<Option<String>> = Vec::new();
+    let mut pending_folder: Option<String> = None;
+    let mut index = 0;
+    while index < tokens.len() {
+        match tokens[index] {
+            Token::Start(ref tag) if tag.name == "h3" => {
+                let (name, next) =
+                    html::text_until_or(&tokens, index + 1, "h3", STRUCTURAL_ELEMENTS);
+                pending_folder = if is_root_folder(tag) { None } else { Some(name) };
+                index = next;
+            }
+            Token::Start(ref tag) if tag.name == "dl" => folders.push(pending_folder.take()),
+            Token::End(ref name) if name == "dl" => {
+                folders.pop();
+            }
+            Token::Start(ref tag) if tag.name == "a" => {
+                let (title, next) =
+                    html::text_until_or(&tokens, index + 1, "a", STRUCTURAL_ELEMENTS);
+                index = next;
+                let url = match tag.attribute("href") {
+                    Some(url) if is_bookmarkable(url) => url,
+                    _ => continue,
+                };
+                let mut tags = folders
+                    .iter()
+                    .filter_map(|folder| folder.clone())
+                    .collect::<Vec<String>>();
+                if let Some(tag_list) = tag.attribute("tags") {
+                    tags.extend(tag_list.split(',').map(String::from));
+                }
+                let added = tag.attribute("add_date").and_then(|date| date.trim().parse().ok());
+                if let Some(link) = import_link(url, &title, &tags, added) {
+                    links.push(link);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    Ok(links)
+}
+
+/// The bookmarks toolbar and unfiled folders are browser roots rather than user folders.
+fn is_root_folder(tag: &Tag) -> bool {
+    tag.attribute("personal_toolbar_folder").is_some() ||
+        tag.attribute("unfiled_bookmarks_folder").is_some()
+}
+
+fn is_bookmarkable(url: &str) -> bool {
+    let url = url.trim().to_lowercase();
+    !url.is_empty() && !url.starts_with("place:") && !url.starts_with("javascript:")
+}
+
+/// Renders a list as a Netscape bookmark file.
+///
+/// Each of `folder_tags` becomes a folder holding the links with that tag; a link with several
+/// of those tags goes into the first matching folder. All other links sit at the top level.
+pub fn render(list: &ReadLaterList, folder_tags: &[String]) -> String {
+    let mut remaining = sorted_links(list);
+    let mut output = String::from(
+        "<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<!-- This is an automatically generated file.
+     It will be read and overwritten.
+     DO NOT EDIT! -->
+<META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+",
+    );
+    for folder_tag in folder_tags {
+        let (in_folder, rest): (Vec<&LinkEntry>, Vec<&LinkEntry>) = remaining
+            .into_iter()
+            .partition(|link| link.tags.contains(folder_tag));
+        remaining = rest;
+        if in_folder.is_empty() {
+            continue;
+        }
+        output.push_str(&format!(
+            "    <DT><H3>{}</H3>\n    <DL><p>\n",
+            html::escape(folder_tag)
+        ));
+        for link in in_folder {
+            output.push_str(&render_link(link, "        "));
+        }
+        output.push_str("    </DL><p>\n");
+    }
+    for link in remaining {
+        output.push_str(&render_link(link, "    "));
+    }
+    output.push_str("</DL><p>\n");
+    output
+}
+
+fn render_link(link: &LinkEntry, indent: &str) -> String {
+    format!(
+        "{}<DT><A HREF=\"{}\"{}{}>{}</A>\n",
+        indent,
+        html::escape(&link.url),
+        match link.added {
+            None => String::from(""),
+            Some(added) => format!(" ADD_DATE=\"{}\"", added),
+        },
+        match link.tags.len() {
+            0 => String::from(""),
+            _ => format!(" TAGS=\"{}\"", html::escape(&link.tags.join(","))),
+        },
+        html::escape(&link.title)
+    )
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeSet;
+use std::result::Result;
+use std::string::String;
+use std::vec::Vec;
+use html::{self, Token};
+use read_later_list::{LinkEntry, ReadLaterList};
+use super::{import_link, merge_duplicates, sorted_links};
+
+/// Parses an OPML outline. Outlines with an `htmlUrl`, `url` or `xmlUrl` attribute become links,
+/// and the text of every enclosing outline without a URL becomes one of their tags.
+pub fn parse(text: &str) -> Result<Vec<LinkEntry>, String> {
+    let tokens = html::tokenize(text);
+    if !tokens.iter().any(|token| matches!(*token, Token::Start(ref tag) if tag.name == "opml")) {
+        return Err(String::from("Not an OPML document"));
+    }
+    let mut links = Vec::new();
+    // One element per open outline, holding its text if it is a category rather than a link
+    let mut categories: Vec<Option<String>> = Vec::new();
+    for token in tokens {
+        match token {
+            Token::Start(ref tag) if tag.name == "outline" => {
+                let title = tag.attribute("text")
+                    .or_else(|| tag.attribute("title"))
+                    .unwrap_or("");
+                let url = tag.attribute("htmlurl")
+                    .or_else(|| tag.attribute("url"))
+                    .or_else(|| tag.attribute("xmlurl"));
+                if let Some(url) = url {
+                    let tags = categories
+                        .iter()
+                        .filter_map(|category| category.clone())
+                        .collect::<Vec<String>>();
+                    if let Some(link) = import_link(url, title, &tags, None) {
+                        links.push(link);
+                    }
+                }
+                if !tag.self_closing {
+                    categories.push(match url {
+                        None if !title.trim().is_empty() => Some(String::from(title)),
+                        _ => None,
+                    });
+                }
+            }
+            Token::End(ref name) if name == "outline" => {
+                categories.pop();
+            }
+            _ => {}
+        }
+    }
+    Ok(merge_duplicates(links))
+}
+
+/// Renders a list as an OPML outline, nesting each link under an outline for each of its tags.
+/// Untagged links sit at the top level.
+pub fn render(list: &ReadLaterList) -> String {
+    let links = sorted_links(list);
+    let tags = links
+        .iter()
+        .flat_map(|link| link.tags.iter())
+        .collect::<BTreeSet<&String>>();
+    let mut output = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<opml version=\"2.0\">
+  <head>
+    <title>Read-later list</title>
+  </head>
+  <body>
+",
+    );
+    for tag in tags {
+        output.push_str(&format!("    <outline text=\"{}\">\n", html::escape(tag)));
+        for link in links.iter().filter(|link| link.tags.contains(tag)) {
+            output.push_str(&render_link(link, "      "));
+        }
+        output.push_str("    </outline>\n");
+    }
+    for link in links.iter().filter(|link| link.tags.is_empty()) {
+        output.push_str(&render_link(link, "    "));
+    }
+    output.push_str("  </body>\n</opml>\n");
+    output
+}
+
+fn render_link(link: &LinkEntry, indent: &str) -> String {
+    format!(
+        "{}<outline text=\"{}\" type=\"link\" url=\"{}\" htmlUrl=\"{}\"/>\n",
+        indent,
+        html::escape(&link.title),
+        html::escape(&link.url),
+        html::escape(&link.url)
+    )
+}
@@ -0,0 +1,139 @@
+use std::result::Result;
+use std::string::String;
+use std::vec::Vec;
+use chrono::{NaiveDate, NaiveTime, TimeZone, Utc};
+use regex::Regex;
+use read_later_list::{LinkEntry, ReadLaterList};
+use super::{import_link, merge_duplicates, sorted_links};
+
+/// Renders a list as an Org outline with a `TODO` headline for each unread link and a `DONE`
+/// headline for each read one. Tags become Org tags and the save date an `ADDED` property.
+pub fn render(list: &ReadLaterList) -> String {
+    sorted_links(list).iter().map(|link| render_link(link)).collect()
+}
+
+fn render_link(link: &LinkEntry) -> String {
+    let url = link.url.replace("[", "%5B").replace("]", "%5D");
+    let title = link.title.replace("[", "{").replace("]", "}");
+    let mut output = format!(
+        "* {} [[{}][{}]]",
+        if link.read { "DONE" } else { "TODO" },
+        url,
+        title
+    );
+    if !link.tags.is_empty() {
+        let tags = link.tags
+            .iter()
+            .map(|tag| org_tag(tag))
+            .collect::<Vec<String>>();
+        output.push_str(&format!(" :{}:", tags.join(":")));
+    }
+    output.push('\n');
+    if let Some(added) = link.added.and_then(|added| Utc.timestamp_opt(added as i64, 0).single()) {
+        output.push_str(&format!(
+            "  :PROPERTIES:\n  :ADDED: {}\n  :END:\n",
+            added.format("[%Y-%m-%d %a %H:%M]")
+        ));
+    }
+    output
+}
+
+/// Org tags may only contain letters, numbers, `_`, `@`, `#` and `%`.
+fn org_tag(tag: &str) -> String {
+    tag.chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() => c,
+            '@' | '#' | '%' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// Parses the headlines of an Org document that contain a link, written either as `[[url]]`,
+/// `[[url][description]]` or a bare URL. Headline tags, including those inherited from parent
+/// headlines, become the link's tags; a `DONE` keyword marks it as read.
+pub fn parse(text: &str) -> Result<Vec<LinkEntry>, String> {
+    lazy_static! {
+        static ref HEADLINE_RE: Regex = Regex::new(r"^(\*+)\s+(?:(TODO|DONE)\s+)?(?:\[#[A-Za-z0-9]\]\s+)?(.*?)(?:\s+:([\w@#%:]+):)?\s*$").unwrap();
+        static ref LINK_RE: Regex = Regex::new(r"\[\[([^\]]+)\](?:\[([^\]]*)\])?\]").unwrap();
+        static ref URL_RE: Regex = Regex::new(r"[A-Za-z][A-Za-z0-9+.-]*://[^\s\]]+").unwrap();
+        static ref ADDED_RE: Regex = Regex::new(r"^\s*:ADDED:\s*\[(\d{4}-\d{2}-\d{2})(?:\s+[^\s\]\d]+)?(?:\s+(\d{1,2}:\d{2}))?").unwrap();
+        static ref END_RE: Regex = Regex::new(r"^\s*:END:\s*$").unwrap();
+    }
+    let mut links = Vec::new();
+    // The level and tags of each enclosing headline
+    let mut ancestors: Vec<(usize, Vec<String>)> = Vec::new();
+    let mut current: Option<LinkEntry> = None;
+    let mut in_drawer = false;
+    for line in text.lines() {
+        let cap = match HEADLINE_RE.captures(line) {
+            Some(cap) => cap,
+            None => {
+                if let Some(ref mut link) = current {
+                    if line.trim() == ":PROPERTIES:" {
+                        in_drawer = true;
+                    } else if in_drawer && END_RE.is_match(line) {
+                        in_drawer = false;
+                    } else if let (true, Some(added)) = (in_drawer, ADDED_RE.captures(line)) {
+                        link.added = parse_timestamp(&added[1], added.get(2).map(|time| time.as_str()));
+                    }
+                }
+                continue;
+            }
+        };
+        links.extend(current.take());
+        in_drawer = false;
+        let level = cap[1].len();
+        while ancestors.last().map(|ancestor| ancestor.0 >= level).unwrap_or(false) {
+            ancestors.pop();
+        }
+        let own_tags = cap.get(4)
+            .map(|tags| {
+                tags.as_str()
+                    .split(':')
+                    .filter(|tag| !tag.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_else(Vec::new);
+        let mut tags = ancestors
+            .iter()
+            .flat_map(|ancestor| ancestor.1.iter().cloned())
+            .collect::<Vec<String>>();
+        tags.extend(own_tags.iter().cloned());
+        ancestors.push((level, own_tags));
+
+        let headline = &cap[3];
+        let (url, title) = match LINK_RE.captures(headline) {
+            Some(link) => {
+                let title = match link.get(2) {
+                    Some(description) => String::from(description.as_str()),
+                    None => LINK_RE.replace(headline, "").trim().to_string(),
+                };
+                (String::from(&link[1]), title)
+            }
+            None => match URL_RE.find(headline) {
+                Some(url) => {
+                    let title = URL_RE.replace(headline, "").trim().to_string();
+                    (String::from(url.as_str()), title)
+                }
+                None => continue,
+            },
+        };
+        current = import_link(&url, &title, &tags, None).map(|mut link| {
+            link.read = cap.get(2).map(|keyword| keyword.as_str()) == Some("DONE");
+            link
+        });
+    }
+    links.extend(current.take());
+    Ok(merge_duplicates(links))
+}
+
+fn parse_timestamp(date: &str, time: Option<&str>) -> Option<u64> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    let time = match time {
+        Some(time) => NaiveTime::parse_from_str(time, "%H:%M").ok()?,
+        None => NaiveTime::from_hms_opt(0, 0, 0)?,
+    };
+    Some(date.and_time(time).and_utc().timestamp() as u64)
+}
@@ -0,0 +1,41 @@
+use std::result::Result;
+use std::string::String;
+use std::vec::Vec;
+use chrono::DateTime;
+use serde_json::{self, Value};
+use super::{import_link, ImportRecords};
+
+/// Parses Pinboard's JSON export. Tags are space-separated, and bookmarks are marked as read
+/// unless `toread` is `yes`.
+pub fn parse(text: &str) -> Result<ImportRecords, String> {
+    let bookmarks = match serde_json::from_str(text) {
+        Ok(Value::Array(bookmarks)) => bookmarks,
+        Ok(_) => return Err(String::from("Not a Pinboard export: expected a JSON array")),
+        Err(err) => return Err(format!("Not a Pinboard export: {}", err)),
+    };
+    let mut links = Vec::new();
+    let mut skipped = Vec::new();
+    for (index, bookmark) in bookmarks.iter().enumerate() {
+        let field = |name: &str| bookmark.get(name).and_then(Value::as_str).unwrap_or("");
+        let url = field("href").trim();
+        if url.is_empty() {
+            skipped.push(format!("bookmark {}: no href", index + 1));
+            continue;
+        }
+        let tags = field("tags")
+            .split_whitespace()
+            .map(String::from)
+            .collect::<Vec<String>>();
+        let added = DateTime::parse_from_rfc3339(field("time"))
+            .ok()
+            .map(|time| time.timestamp() as u64);
+        match import_link(url, field("description"), &tags, added) {
+            Some(mut link) => {
+                link.read = field("toread") != "yes";
+                links.push(link);
+            }
+            None => skipped.push(format!("bookmark {}: invalid record", index + 1)),
+        }
+    }
+    Ok(ImportRecords::new(links, skipped))
+}
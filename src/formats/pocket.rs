@@ -0,0 +1,49 @@
+use std::result::Result;
+use std::string::String;
+use std::vec::Vec;
+use html::{self, Token};
+use read_later_list::LinkEntry;
+use super::import_link;
+
+/// Parses the HTML file produced by Pocket's export. Links under the "Read Archive" heading
+/// are marked as read; the others are unread.
+pub fn parse(text: &str) -> Result<Vec<LinkEntry>, String> {
+    let tokens = html::tokenize(text);
+    let mut links = Vec::new();
+    let mut read = false;
+    let mut index = 0;
+    while index < tokens.len() {
+        if let Token::Start(ref tag) = tokens[index] {
+            match tag.name.as_ref() {
+                "h1" => {
+                    let (heading, next) = html::text_until(&tokens, index + 1, "h1");
+                    let heading = heading.to_lowercase();
+                    read = heading.contains("archive") || heading.trim() == "read";
+                    index = next;
+                    continue;
+                }
+                "a" => {
+                    let (title, next) = html::text_until(&tokens, index + 1, "a");
+                    index = next;
+                    let url = match tag.attribute("href") {
+                        Some(url) => url,
+                        None => continue,
+                    };
+                    let tags: Vec<String> = tag.attribute("tags")
+                        .map(|tags| tags.split(',').map(String::from).collect())
+                        .unwrap_or_default();
+                    let added = tag.attribute("time_added")
+                        .and_then(|time| time.trim().parse().ok());
+                    if let Some(mut link) = import_link(url, &title, &tags, added) {
+                        link.read = read;
+                        links.push(link);
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        index += 1;
+    }
+    Ok(links)
+}
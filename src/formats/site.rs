@@ -0,0 +1,134 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::string::String;
+use std::vec::Vec;
+use html;
+use read_later_list::{LinkEntry, ReadLaterList};
+use super::{slugify, sorted_links};
+
+/// The page template used when none is given. Templates may use the `{{title}}`, `{{content}}`
+/// and `{{root}}` placeholders; `{{root}}` is the relative path from the page to the site root.
+pub const DEFAULT_TEMPLATE: &str = "<!DOCTYPE html>
+<html>
+<head>
+  <meta charset=\"utf-8\">
+  <title>{{title}}</title>
+  <style>
+    body { font-family: sans-serif; max-width: 50em; margin: 2em auto; padding: 0 1em; }
+    .tags a { font-size: 0.8em; margin-left: 0.5em; color: #666; }
+  </style>
+</head>
+<body>
+  <h1><a href=\"{{root}}index.html\">{{title}}</a></h1>
+{{content}}
+</body>
+</html>
+";
+
+/// A generated file, with its path relative to the site root.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    pub path: String,
+    pub contents: String,
+}
+
+/// Renders a list as a static site: an `index.html` listing every link, plus a page under
+/// `tags/` for each tag.
+pub fn render(list: &ReadLaterList, title: &str, template: &str) -> Vec<Page> {
+    let links = sorted_links(list);
+    let tag_paths = tag_paths(&links);
+    let mut index = String::new();
+    if !tag_paths.is_empty() {
+        index.push_str("  <nav class=\"tags\">\n");
+        for (tag, path) in &tag_paths {
+            index.push_str(&format!(
+                "    <a href=\"{}\">{}</a>\n",
+                html::escape(path),
+                html::escape(tag)
+            ));
+        }
+        index.push_str("  </nav>\n");
+    }
+    index.push_str(&render_links(&links, &tag_paths, ""));
+    let mut pages = vec![
+        Page {
+            path: String::from("index.html"),
+            contents: render_page(template, title, &index, ""),
+        },
+    ];
+    for (tag, path) in &tag_paths {
+        let tagged = links
+            .iter()
+            .filter(|link| link.tags.contains(tag))
+            .cloned()
+            .collect::<Vec<&LinkEntry>>();
+        let content = format!(
+            "  <h2>{}</h2>\n{}",
+            html::escape(tag),
+            render_links(&tagged, &tag_paths, "../")
+        );
+        pages.push(Page {
+            path: path.clone(),
+            contents: render_page(template, &format!("{}: {}", title, tag), &content, "../"),
+        });
+    }
+    pages
+}
+
+/// Assigns each tag a unique page path.
+fn tag_paths(links: &[&LinkEntry]) -> BTreeMap<String, String> {
+    let tags = links
+        .iter()
+        .flat_map(|link| link.tags.iter())
+        .collect::<BTreeSet<&String>>();
+    let mut paths = BTreeMap::new();
+    let mut used = HashSet::new();
+    for tag in tags {
+        let slug = match slugify(tag).as_ref() {
+            "" => String::from("tag"),
+            slug => String::from(slug),
+        };
+        let mut path = format!("tags/{}.html", slug);
+        let mut suffix = 2;
+        while used.contains(&path) {
+            path = format!("tags/{}-{}.html", slug, suffix);
+            suffix += 1;
+        }
+        used.insert(path.clone());
+        paths.insert(tag.clone(), path);
+    }
+    paths
+}
+
+fn render_links(links: &[&LinkEntry], tag_paths: &BTreeMap<String, String>, root: &str) -> String {
+    let mut output = String::from("  <ul class=\"links\">\n");
+    for link in links {
+        output.push_str(&format!(
+            "    <li><a href=\"{}\">{}</a>",
+            html::escape(&link.url),
+            html::escape(&link.title)
+        ));
+        if !link.tags.is_empty() {
+            output.push_str("<span class=\"tags\">");
+            for tag in &link.tags {
+                output.push_str(&format!(
+                    "<a href=\"{}{}\">{}</a>",
+                    root,
+                    html::escape(&tag_paths[tag]),
+                    html::escape(tag)
+                ));
+            }
+            output.push_str("</span>");
+        }
+        output.push_str("</li>\n");
+    }
+    output.push_str("  </ul>\n");
+    output
+}
+
+fn render_page(template: &str, title: &str, content: &str, root: &str) -> String {
+    // Substitute the content last so that placeholders inside it are left alone
+    template
+        .replace("{{title}}", &html::escape(title))
+        .replace("{{root}}", root)
+        .replace("{{content}}", content)
+}
@@ -0,0 +1,98 @@
+use std::result::Result;
+use std::string::String;
+use std::vec::Vec;
+use chrono::{NaiveDate, TimeZone, Utc};
+use regex::Regex;
+use read_later_list::{LinkEntry, ReadLaterList};
+use super::{import_link, merge_duplicates, sorted_links};
+
+/// Renders the unread links of a list as todo.txt tasks of the form
+/// `2017-07-14 title url +tag`, with the save date as the task's creation date. Titles that
+/// start like a completion mark, priority or date are put after the URL, and a `+` starting a
+/// word of a title becomes a full-width `＋`, so that they aren't misread as such on import.
+pub fn render(list: &ReadLaterList) -> String {
+    sorted_links(list)
+        .iter()
+        .filter(|link| !link.read)
+        .map(|link| render_link(link))
+        .collect()
+}
+
+fn render_link(link: &LinkEntry) -> String {
+    lazy_static! {
+        static ref LEADING_TOKEN_RE: Regex = Regex::new(r"^(?:x\s|\([A-Z]\)\s|\d{4}-\d{2}-\d{2}(?:\s|$))").unwrap();
+    }
+    let mut words = Vec::new();
+    if let Some(added) = link.added.and_then(|added| Utc.timestamp_opt(added as i64, 0).single()) {
+        words.push(added.format("%Y-%m-%d").to_string());
+    }
+    let title = link
+        .title
+        .split_whitespace()
+        .map(|word| if word.starts_with('+') {
+            word.replacen('+', "＋", 1)
+        } else {
+            String::from(word)
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+    if link.title == link.url {
+        words.push(link.url.clone());
+    } else if LEADING_TOKEN_RE.is_match(&title) {
+        words.push(link.url.clone());
+        words.push(title);
+    } else {
+        words.push(title);
+        words.push(link.url.clone());
+    }
+    for tag in &link.tags {
+        words.push(format!("+{}", tag.split_whitespace().collect::<Vec<&str>>().join("-")));
+    }
+    format!("{}\n", words.join(" "))
+}
+
+/// Parses the todo.txt tasks that contain a URL. Completed tasks are marked as read,
+/// `+project`s become tags and the creation date becomes the date the link was added.
+pub fn parse(text: &str) -> Result<Vec<LinkEntry>, String> {
+    lazy_static! {
+        static ref TASK_RE: Regex = Regex::new(r"^(x\s+)?(?:\([A-Z]\)\s+)?(?:(\d{4}-\d{2}-\d{2})\s+)?(?:(\d{4}-\d{2}-\d{2})\s+)?(.*)$").unwrap();
+        static ref URL_RE: Regex = Regex::new(r"(?:^|\s)([A-Za-z][A-Za-z0-9+.-]*://\S+)").unwrap();
+        static ref PROJECT_RE: Regex = Regex::new(r"(?:^|\s)\+(\S+)").unwrap();
+    }
+    let mut links = Vec::new();
+    for line in text.lines() {
+        let cap = match TASK_RE.captures(line.trim()) {
+            Some(cap) => cap,
+            None => continue,
+        };
+        let description = &cap[4];
+        let url = match URL_RE.captures(description) {
+            Some(url) => String::from(&url[1]),
+            None => continue,
+        };
+        let read = cap.get(1).is_some();
+        // A completed task's first date is its completion date, followed by its creation date
+        let created = if read {
+            cap.get(3).or_else(|| cap.get(2))
+        } else {
+            cap.get(2)
+        };
+        let added = created.and_then(|date| {
+            NaiveDate::parse_from_str(date.as_str(), "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|date| date.and_utc().timestamp() as u64)
+        });
+        let tags = PROJECT_RE
+            .captures_iter(description)
+            .map(|project| String::from(&project[1]))
+            .collect::<Vec<String>>();
+        let without_url = URL_RE.replace_all(description, "");
+        let title = PROJECT_RE.replace_all(&without_url, "");
+        if let Some(mut link) = import_link(&url, &title, &tags, added) {
+            link.read = read;
+            links.push(link);
+        }
+    }
+    Ok(merge_duplicates(links))
+}
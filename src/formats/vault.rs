@@ -0,0 +1,125 @@
+use std::string::String;
+use std::vec::Vec;
+use chrono::{TimeZone, Utc};
+use read_later_list::{LinkEntry, ReadLaterList};
+use super::site::Page;
+use super::{short_hash, slugify, sorted_links};
+
+/// The longest slug used in a note's file name, before the hash suffix.
+const MAX_SLUG_LENGTH: usize = 60;
+/// The front matter field holding a hash of the other fields, which tells whether a note's
+/// link has changed since it was exported.
+const HASH_FIELD: &str = "readlater_hash";
+
+/// Renders a list as a vault of Markdown notes, one per link, with the link's fields in YAML
+/// front matter.
+pub fn render(list: &ReadLaterList) -> Vec<Page> {
+    sorted_links(list)
+        .iter()
+        .map(|link| {
+            Page {
+                path: note_path(link),
+                contents: render_note(link),
+            }
+        })
+        .collect()
+}
+
+/// Returns the file name of a link's note. Names are derived from the URL alone, so a note
+/// keeps its name when the link's other fields change.
+pub fn note_path(link: &LinkEntry) -> String {
+    let address = match link.url.find("://") {
+        Some(index) => &link.url[index + 3..],
+        None => &link.url[..],
+    };
+    let slug = slugify(address)
+        .chars()
+        .take(MAX_SLUG_LENGTH)
+        .collect::<String>();
+    let slug = slug.trim_end_matches('-');
+    let hash = short_hash(&link.url);
+    if slug.is_empty() {
+        format!("{}.md", hash)
+    } else {
+        format!("{}-{}.md", slug, hash)
+    }
+}
+
+fn render_note(link: &LinkEntry) -> String {
+    let fields = render_fields(link);
+    format!(
+        "---\n{}{}: {}\n---\n\n# {}\n\n<{}>\n",
+        fields,
+        HASH_FIELD,
+        yaml_string(&short_hash(&fields)),
+        link.title,
+        link.url
+    )
+}
+
+fn render_fields(link: &LinkEntry) -> String {
+    let mut output = String::new();
+    output.push_str(&format!("url: {}\n", yaml_string(&link.url)));
+    output.push_str(&format!("title: {}\n", yaml_string(&link.title)));
+    if link.tags.is_empty() {
+        output.push_str("tags: []\n");
+    } else {
+        output.push_str("tags:\n");
+        for tag in &link.tags {
+            output.push_str(&format!("  - {}\n", yaml_string(tag)));
+        }
+    }
+    if let Some(added) = link.added.and_then(|added| Utc.timestamp_opt(added as i64, 0).single()) {
+        output.push_str(&format!("added: {}\n", added.format("%Y-%m-%dT%H:%M:%SZ")));
+    }
+    output.push_str(&format!("read: {}\n", link.read));
+    output
+}
+
+/// Splits a note into its front matter, without the `---` lines, and its body.
+fn split_note(note: &str) -> Option<(&str, &str)> {
+    let rest = note.strip_prefix("---\n")?;
+    let end = if rest.starts_with("---\n") { 0 } else { rest.find("\n---\n")? + 1 };
+    Some((&rest[..end], &rest[end + 4..]))
+}
+
+/// Returns the hash that an exported note's front matter was written with, or `None` if the
+/// note wasn't written by an export.
+pub fn note_hash(note: &str) -> Option<&str> {
+    let prefix = format!("{}: ", HASH_FIELD);
+    split_note(note)?
+        .0
+        .lines()
+        .find_map(|line| line.strip_prefix(prefix.as_str()))
+        .map(|hash| hash.trim_matches('"'))
+}
+
+/// Updates an existing note to match a newly rendered one. Returns `None` if the link hasn't
+/// changed since the note was written; otherwise replaces the front matter but keeps the
+/// existing body, so that edits made to the note in the vault survive.
+pub fn update_note(existing: &str, note: &str) -> Option<String> {
+    if note_hash(existing).is_some() && note_hash(existing) == note_hash(note) {
+        return None;
+    }
+    let front_matter_length = note.len() - split_note(note)?.1.len();
+    match split_note(existing) {
+        Some((_, body)) => Some(format!("{}{}", &note[..front_matter_length], body)),
+        None => Some(format!("{}\n{}", &note[..front_matter_length], existing)),
+    }
+}
+
+/// Quotes a string as a double-quoted YAML scalar.
+fn yaml_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,379 @@
+use std::result::Result;
+use std::string::String;
+use std::vec::Vec;
+use regex::Regex;
+
+/// An opening tag with its lowercased name and attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tag {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub self_closing: bool,
+}
+
+impl Tag {
+    /// Looks up an attribute by name, ignoring case.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.0 == name)
+            .map(|attribute| attribute.1.as_ref())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Start(Tag),
+    End(String),
+    Text(String),
+}
+
+/// Elements whose content is raw text rather than markup.
+const RAW_TEXT_ELEMENTS: [&str; 4] = ["script", "style", "textarea", "title"];
+
+/// Tags that are dropped along with their content when sanitizing HTML.
+const SKIPPED_ELEMENTS: [&str; 12] = [
+    "head", "script", "style", "noscript", "template", "iframe", "object", "embed", "form",
+    "svg", "math", "nav",
+];
+
+/// Tags that are kept when sanitizing HTML; any other tag is replaced by its content.
+const ALLOWED_ELEMENTS: [&str; 44] = [
+    "p", "br", "hr", "h1", "h2", "h3", "h4", "h5", "h6", "ul", "ol", "li", "dl", "dt", "dd",
+    "blockquote", "pre", "code", "em", "strong", "b", "i", "u", "s", "sub", "sup", "small",
+    "mark", "q", "cite", "abbr", "a", "img", "figure", "figcaption", "table", "thead", "tbody",
+    "tfoot", "tr", "th", "td", "div", "span",
+];
+
+const VOID_ELEMENTS: [&str; 3] = ["br", "hr", "img"];
+
+/// Splits an HTML or XML document into tags and entity-decoded text.
+///
+/// This is a forgiving tokenizer rather than a full HTML5 parser: comments, doctypes and
+/// processing instructions are dropped, and malformed markup is passed through as text.
+pub fn tokenize(html: &str) -> Vec<Token> {
+    lazy_static! {
+        static ref TAG_RE: Regex = Regex::new(r"^<(/?)([A-Za-z][A-Za-z0-9:_-]*)((?:[^>\x22']|\x22[^\x22]*\x22|'[^']*')*?)(/?)>").unwrap();
+    }
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            text.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        if rest.starts_with("<!--") {
+            rest = skip_past(rest, "-->");
+            continue;
+        }
+        if rest.starts_with("<![CDATA[") {
+            let content = &rest["<![CDATA[".len()..];
+            let end = content.find("]]>").unwrap_or(content.len());
+            flush_text(&mut tokens, &mut text);
+            tokens.push(Token::Text(String::from(&content[..end])));
+            rest = skip_past(content, "]]>");
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = skip_past(rest, ">");
+            continue;
+        }
+        let (token, length) = match TAG_RE.captures(rest) {
+            None => (None, 0),
+            Some(cap) => {
+                let name = cap[2].to_lowercase();
+                let token = match &cap[1] {
+                    "/" => Token::End(name),
+                    _ => Token::Start(Tag {
+                        name,
+                        attributes: parse_attributes(&cap[3]),
+                        self_closing: &cap[4] == "/",
+                    }),
+                };
+                (Some(token), cap[0].len())
+            }
+        };
+        match token {
+            None => {
+                text.push('<');
+                rest = &rest[1..];
+            }
+            Some(token) => {
+                flush_text(&mut tokens, &mut text);
+                rest = &rest[length..];
+                let raw_text_element = match token {
+                    Token::Start(ref tag) if !tag.self_closing &&
+                        RAW_TEXT_ELEMENTS.contains(&tag.name.as_ref()) => Some(tag.name.clone()),
+                    _ => None,
+                };
+                tokens.push(token);
+                if let Some(name) = raw_text_element {
+                    let end = rest.to_ascii_lowercase()
+                        .find(&format!("</{}", name))
+                        .unwrap_or(rest.len());
+                    let content = &rest[..end];
+                    if !content.is_empty() {
+                        tokens.push(Token::Text(match name.as_ref() {
+                            "script" | "style" => String::from(content),
+                            _ => unescape(content),
+                        }));
+                    }
+                    rest = &rest[end..];
+                }
+            }
+        }
+    }
+    flush_text(&mut tokens, &mut text);
+    tokens
+}
+
+/// Collects the text from `tokens[start]` up to the closing `name` tag, returning it with the
+/// index of the token after that tag.
+pub fn text_until(tokens: &[Token], start: usize, name: &str) -> (String, usize) {
+    text_until_or(tokens, start, name, &[])
+}
+
+/// Like `text_until`, but also stops before the start of any `stop_elements`, for markup that
+/// leaves out closing tags. Returns the index of that start tag in that case.
+pub fn text_until_or(
+    tokens: &[Token],
+    start: usize,
+    name: &str,
+    stop_elements: &[&str],
+) -> (String, usize) {
+    let mut text = String::new();
+    let mut index = start;
+    while index < tokens.len() {
+        match tokens[index] {
+            Token::Text(ref content) => text.push_str(content),
+            Token::End(ref end) if end == name => return (text, index + 1),
+            Token::Start(ref tag) if stop_elements.contains(&tag.name.as_str()) => {
+                return (text, index)
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    (text, index)
+}
+
+/// Writes tokens back out as HTML. The content of `<script>` and `<style>` elements is written
+/// as is, while other text and attribute values are escaped.
+pub fn serialize(tokens: &[Token]) -> String {
+    let mut html = String::new();
+    let mut raw_text = false;
+    for token in tokens {
+        match *token {
+            Token::Start(ref tag) => {
+                html.push('<');
+                html.push_str(&tag.name);
+                for attribute in &tag.attributes {
+                    html.push_str(&format!(" {}=\"{}\"", attribute.0, escape(&attribute.1)));
+                }
+                html.push_str(if tag.self_closing { "/>" } else { ">" });
+                raw_text = !tag.self_closing && (tag.name == "script" || tag.name == "style");
+                continue;
+            }
+            Token::End(ref name) => html.push_str(&format!("</{}>", name)),
+            Token::Text(ref text) if raw_text => html.push_str(text),
+            Token::Text(ref text) => html.push_str(&escape(text)),
+        }
+        raw_text = false;
+    }
+    html
+}
+
+/// Converts arbitrary HTML into well-formed XHTML, keeping only simple formatting elements and
+/// embedded images so that the result is readable offline and safe to display.
+pub fn sanitize(content: &str) -> String {
+    let mut output = String::new();
+    let mut open: Vec<String> = Vec::new();
+    let mut skipping: Option<(String, usize)> = None;
+    for token in tokenize(content) {
+        if let Some((name, depth)) = skipping.take() {
+            skipping = match token {
+                Token::Start(ref tag) if tag.name == name && !tag.self_closing => {
+                    Some((name, depth + 1))
+                }
+                Token::End(ref end) if *end == name => {
+                    if depth == 0 {
+                        None
+                    } else {
+                        Some((name, depth - 1))
+                    }
+                }
+                _ => Some((name, depth)),
+            };
+            continue;
+        }
+        match token {
+            Token::Text(text) => output.push_str(&escape(&text)),
+            Token::Start(tag) => {
+                if SKIPPED_ELEMENTS.contains(&tag.name.as_ref()) {
+                    if !tag.self_closing {
+                        skipping = Some((tag.name, 0));
+                    }
+                    continue;
+                }
+                if !ALLOWED_ELEMENTS.contains(&tag.name.as_ref()) {
+                    continue;
+                }
+                let mut attributes = String::new();
+                let mut keep = |name: &str, value: Option<&str>| {
+                    if let Some(value) = value {
+                        attributes.push_str(&format!(" {}=\"{}\"", name, escape(value)));
+                    }
+                };
+                match tag.name.as_ref() {
+                    "a" => keep("href", tag.attribute("href").filter(|href| is_absolute(href))),
+                    "img" => {
+                        // Remote images would not load offline, so only embedded ones are kept
+                        match tag.attribute("src") {
+                            Some(src) if src.starts_with("data:image/") => {
+                                keep("src", Some(src));
+                                keep("alt", Some(tag.attribute("alt").unwrap_or("")));
+                            }
+                            _ => continue,
+                        }
+                    }
+                    "td" | "th" => {
+                        keep("colspan", tag.attribute("colspan"));
+                        keep("rowspan", tag.attribute("rowspan"));
+                    }
+                    _ => {}
+                }
+                if VOID_ELEMENTS.contains(&tag.name.as_ref()) {
+                    output.push_str(&format!("<{}{}/>", tag.name, attributes));
+                } else if tag.self_closing {
+                    output.push_str(&format!("<{}{}></{}>", tag.name, attributes, tag.name));
+                } else {
+                    output.push_str(&format!("<{}{}>", tag.name, attributes));
+                    open.push(tag.name);
+                }
+            }
+            Token::End(name) => {
+                if let Some(index) = open.iter().rposition(|tag| *tag == name) {
+                    for tag in open.drain(index..).rev() {
+                        output.push_str(&format!("</{}>", tag));
+                    }
+                }
+            }
+        }
+    }
+    for tag in open.iter().rev() {
+        output.push_str(&format!("</{}>", tag));
+    }
+    output
+}
+
+fn is_absolute(url: &str) -> bool {
+    let lowercase = url.to_lowercase();
+    lowercase.starts_with("http://") || lowercase.starts_with("https://") ||
+        lowercase.starts_with("mailto:")
+}
+
+fn skip_past<'a>(text: &'a str, terminator: &str) -> &'a str {
+    match text.find(terminator) {
+        None => "",
+        Some(index) => &text[index + terminator.len()..],
+    }
+}
+
+fn flush_text(tokens: &mut Vec<Token>, text: &mut String) {
+    if !text.is_empty() {
+        tokens.push(Token::Text(unescape(text)));
+        text.clear();
+    }
+}
+
+fn parse_attributes(text: &str) -> Vec<(String, String)> {
+    lazy_static! {
+        static ref ATTRIBUTE_RE: Regex = Regex::new(r#"([^\s=/>"']+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+)))?"#).unwrap();
+    }
+    ATTRIBUTE_RE
+        .captures_iter(text)
+        .map(|cap| {
+            let value = cap.get(2)
+                .or_else(|| cap.get(3))
+                .or_else(|| cap.get(4))
+                .map(|value| unescape(value.as_str()))
+                .unwrap_or_default();
+            (cap[1].to_lowercase(), value)
+        })
+        .collect()
+}
+
+/// Escapes text for use in HTML or XML content and attribute values.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Decodes character references such as `&amp;`, `&#39;` and `&#x2014;`.
+pub fn unescape(text: &str) -> String {
+    lazy_static! {
+        static ref ENTITY_RE: Regex = Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[A-Za-z]+);").unwrap();
+    }
+    if !text.contains('&') {
+        return String::from(text);
+    }
+    ENTITY_RE
+        .replace_all(text, |cap: &::regex::Captures| match decode_entity(&cap[1]) {
+            Ok(decoded) => decoded,
+            Err(_) => String::from(&cap[0]),
+        })
+        .into_owned()
+}
+
+fn decode_entity(entity: &str) -> Result<String, String> {
+    let code_point = if let Some(hex) = entity
+        .strip_prefix("#x")
+        .or_else(|| entity.strip_prefix("#X"))
+    {
+        u32::from_str_radix(hex, 16).map_err(|err| err.to_string())?
+    } else if let Some(decimal) = entity.strip_prefix('#') {
+        decimal.parse::<u32>().map_err(|err| err.to_string())?
+    } else {
+        let decoded = match entity {
+            "amp" => "&",
+            "lt" => "<",
+            "gt" => ">",
+            "quot" => "\"",
+            "apos" => "'",
+            "nbsp" => "\u{a0}",
+            "ndash" => "\u{2013}",
+            "mdash" => "\u{2014}",
+            "hellip" => "\u{2026}",
+            "lsquo" => "\u{2018}",
+            "rsquo" => "\u{2019}",
+            "ldquo" => "\u{201c}",
+            "rdquo" => "\u{201d}",
+            "laquo" => "\u{ab}",
+            "raquo" => "\u{bb}",
+            "middot" => "\u{b7}",
+            "bull" => "\u{2022}",
+            "copy" => "\u{a9}",
+            "reg" => "\u{ae}",
+            "trade" => "\u{2122}",
+            _ => return Err(format!("Unknown entity {}", entity)),
+        };
+        return Ok(String::from(decoded));
+    };
+    ::std::char::from_u32(code_point)
+        .map(|c| c.to_string())
+        .ok_or_else(|| format!("Invalid code point {}", code_point))
+}
@@ -0,0 +1,188 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+use std::string::String;
+use std::vec::Vec;
+
+/// BM25 term frequency saturation
+const K1: f64 = 1.2;
+/// BM25 document length normalization
+const B: f64 = 0.75;
+
+/// An inverted index over the text of archived articles, ranking matches with BM25.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    /// How many terms each indexed link's text has
+    lengths: BTreeMap<String, usize>,
+    /// How often each term occurs in the text of each link that has it
+    postings: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchResult {
+    pub url: String,
+    pub score: f64,
+}
+
+impl SearchIndex {
+    pub fn new() -> SearchIndex {
+        SearchIndex::default()
+    }
+
+    /// Returns how many links are indexed.
+    pub fn len(&self) -> usize {
+        self.lengths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    pub fn contains(&self, url: &str) -> bool {
+        self.lengths.contains_key(url)
+    }
+
+    /// Indexes a link's text, replacing whatever was indexed for it before.
+    pub fn add(&mut self, url: &str, text: &str) {
+        self.remove(url);
+        let terms = terms(text);
+        for term in &terms {
+            *self.postings
+                .entry(term.clone())
+                .or_default()
+                .entry(String::from(url))
+                .or_insert(0) += 1;
+        }
+        self.lengths.insert(String::from(url), terms.len());
+    }
+
+    pub fn remove(&mut self, url: &str) {
+        if self.lengths.remove(url).is_none() {
+            return;
+        }
+        self.postings.retain(|_, frequencies| {
+            frequencies.remove(url);
+            !frequencies.is_empty()
+        });
+    }
+
+    /// Moves what is indexed for a link to a new URL.
+    pub fn rename(&mut self, url: &str, new_url: &str) {
+        let length = match self.lengths.remove(url) {
+            Some(length) => length,
+            None => return,
+        };
+        self.remove(new_url);
+        self.lengths.insert(String::from(new_url), length);
+        for frequencies in self.postings.values_mut() {
+            if let Some(frequency) = frequencies.remove(url) {
+                frequencies.insert(String::from(new_url), frequency);
+            }
+        }
+    }
+
+    /// Returns the links whose text has any of the terms in `query`, best matches first.
+    pub fn search(&self, query: &str) -> Vec<SearchResult> {
+        if self.lengths.is_empty() {
+            return Vec::new();
+        }
+        let count = self.lengths.len() as f64;
+        let average_length = self.lengths.values().sum::<usize>() as f64 / count;
+        let mut query_terms = terms(query);
+        query_terms.sort();
+        query_terms.dedup();
+        let mut scores: BTreeMap<&str, f64> = BTreeMap::new();
+        for term in &query_terms {
+            let frequencies = match self.postings.get(term) {
+                Some(frequencies) => frequencies,
+                None => continue,
+            };
+            let matches = frequencies.len() as f64;
+            let idf = ((count - matches + 0.5) / (matches + 0.5) + 1.0).ln();
+            for (url, &frequency) in frequencies {
+                let frequency = frequency as f64;
+                let length = self.lengths[url] as f64 / average_length.max(1.0);
+                *scores.entry(url).or_insert(0.0) += idf * frequency * (K1 + 1.0) /
+                    (frequency + K1 * (1.0 - B + B * length));
+            }
+        }
+        let mut results = scores
+            .into_iter()
+            .map(|score| {
+                SearchResult {
+                    url: String::from(score.0),
+                    score: score.1,
+                }
+            })
+            .collect::<Vec<SearchResult>>();
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.url.cmp(&b.url))
+        });
+        results
+    }
+}
+
+/// Splits text into lowercase terms of letters and digits.
+pub fn terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| term.to_lowercase())
+        .collect()
+}
+
+/// Returns the passage of at most `length` words of `text` with the most terms from `query`,
+/// passing the matching words to `highlight` and marking cut text with ellipses.
+pub fn snippet<F>(text: &str, query: &str, length: usize, highlight: F) -> String
+where
+    F: Fn(&str) -> String,
+{
+    let query_terms = terms(query).into_iter().collect::<HashSet<String>>();
+    let words = text.split_whitespace().collect::<Vec<&str>>();
+    let hits = words
+        .iter()
+        .map(|word| terms(word).iter().any(|term| query_terms.contains(term)))
+        .collect::<Vec<bool>>();
+    let length = length.max(1).min(words.len());
+    // Slide a window over the words, keeping the first one with the most hits
+    let mut start = 0;
+    let mut best = hits[..length].iter().filter(|&&hit| hit).count();
+    let mut window = best;
+    for end in length..words.len() {
+        window = window + hits[end] as usize - hits[end - length] as usize;
+        if window > best {
+            best = window;
+            start = end + 1 - length;
+        }
+    }
+    let passage = (start..start + length)
+        .map(|index| if hits[index] {
+            highlight_word(words[index], &highlight)
+        } else {
+            String::from(words[index])
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+    format!(
+        "{}{}{}",
+        if start > 0 { "…" } else { "" },
+        passage,
+        if start + length < words.len() { "…" } else { "" }
+    )
+}
+
+/// Highlights a word without the punctuation around it.
+fn highlight_word<F>(word: &str, highlight: &F) -> String
+where
+    F: Fn(&str) -> String,
+{
+    let core = word.trim_matches(|c: char| !c.is_alphanumeric());
+    let start = word.find(core).unwrap_or(0);
+    format!(
+        "{}{}{}",
+        &word[..start],
+        highlight(core),
+        &word[start + core.len()..]
+    )
+}
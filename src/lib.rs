@@ -1,8 +1,24 @@
 #[macro_use]
 extern crate serde_derive;
 extern crate serde;
+extern crate serde_json;
 #[macro_use]
 extern crate lazy_static;
 extern crate regex;
+extern crate csv as csv_crate;
+extern crate chrono;
+extern crate rusqlite;
+extern crate ureq;
+extern crate zip;
 
+pub mod util;
 pub mod read_later_list;
+pub mod crdt;
+pub mod html;
+pub mod fetch;
+pub mod archive;
+pub mod readability;
+pub mod index;
+pub mod diff;
+pub mod check;
+pub mod formats;
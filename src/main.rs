@@ -1,21 +1,32 @@
 extern crate open_read_later;
 extern crate clap;
-extern crate regex;
 #[macro_use]
 extern crate serde_json;
 
-mod util;
-
 use std::env;
 use std::error::Error;
 use std::path::PathBuf;
-use open_read_later::read_later_list::{ReadLaterList, LinkEntry};
-use util::{prompt, read_from_file, overwrite_file};
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use open_read_later::read_later_list::{self, ReadLaterList, LinkEntry, LinkEntryBuilder};
+use open_read_later::archive::{self, ContentStore, SnapshotInfo};
+use open_read_later::check::{self, CheckOptions, Outcome};
+use open_read_later::crdt::{self, ReplicatedList};
+use open_read_later::readability::{self, Article};
+use open_read_later::diff;
+use open_read_later::index;
+use open_read_later::fetch::{self, Metadata};
+use open_read_later::formats::{bibtex, chromium, csv, epub, feed, firefox, instapaper, markdown,
+                               netscape, opml, org, pinboard, pocket, site, todotxt, vault,
+                               ImportRecords, ImportSummary};
+use open_read_later::formats::csv::ColumnMapping;
+use open_read_later::util::{prompt, read_from_file, overwrite_file, open_in_browser};
 use clap::{Arg, App, SubCommand, ArgMatches};
-use regex::RegexBuilder;
 
 #[allow(unused_imports)]
-use util::trace;
+use open_read_later::util::trace;
 
 fn main() {
     match run() {
@@ -42,14 +53,34 @@ fn run() -> Result<i32, Box<Error>> {
     let list_text = read_from_file(list_file_path).unwrap_or(String::from(""));
 
     let mut read_later_list = ReadLaterList::parse(&list_text)?;
+    let content_store = ContentStore::for_list_file(list_file_path);
 
     match args.subcommand() {
-        ("list", Some(_)) => list(&read_later_list, json)?,
-        ("save", Some(save_args)) => save(&mut read_later_list, save_args)?,
+        ("list", Some(list_args)) => list(&read_later_list, list_args, json)?,
+        ("save", Some(save_args)) => save(&mut read_later_list, &content_store, save_args)?,
         ("show", Some(show_args)) => show(&read_later_list, show_args, json)?,
-        ("delete", Some(delete_args)) => delete(&mut read_later_list, delete_args),
+        ("delete", Some(delete_args)) => {
+            delete(&mut read_later_list, &content_store, delete_args)?
+        }
         ("tag", Some(tags_args)) => tag(&mut read_later_list, tags_args)?,
-        ("search", Some(search_args)) => search(&read_later_list, search_args, json)?,
+        ("search", Some(search_args)) => {
+            search(&read_later_list, &content_store, search_args, json)?
+        }
+        ("import", Some(import_args)) => import(&mut read_later_list, import_args, json)?,
+        ("export", Some(export_args)) => export(&read_later_list, &content_store, export_args)?,
+        ("sync", Some(sync_args)) => {
+            read_later_list = sync(&read_later_list, list_file_path, sync_args)?
+        }
+        ("archive-content", Some(archive_args)) => {
+            archive_content(&mut read_later_list, &content_store, archive_args)?
+        }
+        ("open", Some(open_args)) => open(&read_later_list, &content_store, open_args)?,
+        ("watch-changes", Some(watch_args)) => {
+            watch_changes(&mut read_later_list, &content_store, watch_args, json)?
+        }
+        ("check", Some(check_args)) => {
+            check(&mut read_later_list, &content_store, check_args, json)?
+        }
         _ => println!("{}", args.usage()),
     };
 
@@ -91,15 +122,45 @@ fn parse_args<'a>(default_list_file: &'a PathBuf) -> ArgMatches<'a> {
                          .long("tags")
                          .takes_value(true)
                          .value_name("TAGS")
-                         .multiple(true)))
+                         .multiple(true))
+                    .arg(Arg::with_name("offline")
+                         .help("doesn't fetch the page to suggest a title and record its metadata")
+                         .long("offline"))
+                    .arg(Arg::with_name("timeout")
+                         .help("how many seconds to wait for the page when fetching its title")
+                         .long("timeout")
+                         .takes_value(true)
+                         .value_name("SECONDS")
+                         .validator(is_whole_number)
+                         .default_value("10"))
+                    .arg(Arg::with_name("archive")
+                         .help("also stores a copy of the page in the content store")
+                         .long("archive")
+                         .conflicts_with("offline"))
+                    .arg(Arg::with_name("expand")
+                         .help("follows redirects, such as from a short link, and saves the URL \
+                                they lead to")
+                         .long("expand")
+                         .conflicts_with("offline")))
         .subcommand(SubCommand::with_name("list")
-                    .about("lists link entries"))
+                    .about("lists link entries")
+                    .arg(Arg::with_name("max_minutes")
+                         .help("only lists links that take at most this long to read, leaving \
+                                out links without a reading time")
+                         .long("max-minutes")
+                         .takes_value(true)
+                         .value_name("MINUTES")
+                         .validator(is_whole_number)))
         .subcommand(SubCommand::with_name("search")
                     .about("searches link entries by keyword")
                     .arg(Arg::with_name("keyword")
                          .takes_value(true)
                          .value_name("KEYWORD")
-                         .required(true)))
+                         .required(true))
+                    .arg(Arg::with_name("content")
+                         .help("searches the text of archived pages for any of the words in \
+                                KEYWORD, best matches first")
+                         .long("content")))
         .subcommand(SubCommand::with_name("show")
                     .about("shows a link entry")
                     .arg(Arg::with_name("url")
@@ -142,10 +203,236 @@ fn parse_args<'a>(default_list_file: &'a PathBuf) -> ArgMatches<'a> {
                                      .value_name("TAG")
                                      .multiple(true)
                                      .required(true))))
+        .subcommand(SubCommand::with_name("import")
+                    .about("imports links from another format")
+                    .arg(Arg::with_name("format")
+                         .help("the format of the file to import")
+                         .long("format")
+                         .takes_value(true)
+                         .value_name("FORMAT")
+                         .possible_values(&["netscape", "pocket", "csv", "tsv", "opml",
+                                            "markdown", "instapaper", "pinboard", "bibtex",
+                                            "org", "todotxt"])
+                         .required_unless("from")
+                         .conflicts_with("from"))
+                    .arg(Arg::with_name("from")
+                         .help("imports bookmarks from a browser profile instead of a file")
+                         .long("from")
+                         .takes_value(true)
+                         .value_name("BROWSER")
+                         .possible_values(&["firefox", "chromium"]))
+                    .arg(Arg::with_name("columns")
+                         .help("for csv and tsv, maps fields to column headers, e.g. \
+                                url=Link,title=Name; the fields are url, title, tags, added, read, description, \
+                                author, site_name, published, canonical_url, language, \
+                                aliases, word_count and reading_minutes")
+                         .long("columns")
+                         .takes_value(true)
+                         .value_name("FIELD[=HEADER]")
+                         .multiple(true)
+                         .require_delimiter(true))
+                    .arg(Arg::with_name("file")
+                         .help("the file to import, or the profile directory for --from")
+                         .takes_value(true)
+                         .value_name("FILE")
+                         .required(true)))
+        .subcommand(SubCommand::with_name("export")
+                    .about("exports links to another format")
+                    .arg(Arg::with_name("format")
+                         .help("the format to export to")
+                         .long("format")
+                         .takes_value(true)
+                         .value_name("FORMAT")
+                         .possible_values(&["netscape", "csv", "tsv", "opml", "atom", "rss",
+                                            "html", "markdown", "bibtex", "org", "vault",
+                                            "todotxt", "epub"])
+                         .required(true))
+                    .arg(Arg::with_name("out")
+                         .help("the file to write to instead of stdout, or the directory for html \
+                                and vault; required for epub")
+                         .long("out")
+                         .takes_value(true)
+                         .value_name("PATH")
+                         .required_if("format", "html")
+                         .required_if("format", "vault")
+                         .required_if("format", "epub"))
+                    .arg(Arg::with_name("query")
+                         .help("only exports links matching this keyword")
+                         .long("query")
+                         .takes_value(true)
+                         .value_name("KEYWORD"))
+                    .arg(Arg::with_name("columns")
+                         .help("for csv and tsv, the fields to export and their column headers; \
+                                any of url, title, tags, added, read, description, author, site_name, \
+                                published, canonical_url, language, aliases, word_count and \
+                                reading_minutes (default url, title, tags, added and read)")
+                         .long("columns")
+                         .takes_value(true)
+                         .value_name("FIELD[=HEADER]")
+                         .multiple(true)
+                         .require_delimiter(true))
+                    .arg(Arg::with_name("folders")
+                         .help("for netscape, tags to group into bookmark folders")
+                         .long("folders")
+                         .takes_value(true)
+                         .value_name("TAG")
+                         .multiple(true))
+                    .arg(Arg::with_name("feed_title")
+                         .help("for atom and rss, the title of the feed")
+                         .long("feed-title")
+                         .takes_value(true)
+                         .value_name("TITLE")
+                         .default_value("Read-later list"))
+                    .arg(Arg::with_name("feed_link")
+                         .help("for atom and rss, the URL the feed is published at")
+                         .long("feed-link")
+                         .takes_value(true)
+                         .value_name("URL")
+                         .default_value("https://github.com/jdormit/open-read-later"))
+                    .arg(Arg::with_name("group_by_tag")
+                         .help("for markdown, groups links under a heading for each tag")
+                         .long("group-by-tag"))
+                    .arg(Arg::with_name("template")
+                         .help("for html, a page template using {{title}}, {{content}} and {{root}}")
+                         .long("template")
+                         .takes_value(true)
+                         .value_name("FILE"))
+                    .arg(Arg::with_name("book_title")
+                         .help("for epub, the title of the book")
+                         .long("book-title")
+                         .takes_value(true)
+                         .value_name("TITLE")
+                         .default_value("Reading list"))
+                    .arg(Arg::with_name("order")
+                         .help("for epub, whether to order chapters by save date or by tag")
+                         .long("order")
+                         .takes_value(true)
+                         .value_name("ORDER")
+                         .possible_values(&["date", "tag"])
+                         .default_value("date"))
+                    .arg(Arg::with_name("incremental")
+                         .help("for vault, only rewrites the front matter of notes whose link \
+                                has changed, keeping edits to their text")
+                         .long("incremental"))
+                    .arg(Arg::with_name("prune")
+                         .help("for vault, removes the notes of links that are no longer in the \
+                                list")
+                         .long("prune")))
+        .subcommand(SubCommand::with_name("sync")
+                    .about("merges the list with a shared replica state file")
+                    .arg(Arg::with_name("state_file")
+                         .help("the shared state file to merge from and export to")
+                         .takes_value(true)
+                         .value_name("STATE_FILE")
+                         .required(true))
+                    .arg(Arg::with_name("replica")
+                         .help("the ID of this device's replica, used when it is first created; \
+                                each device needs its own [default: a random ID]")
+                         .long("replica")
+                         .takes_value(true)
+                         .value_name("ID")))
+        .subcommand(SubCommand::with_name("archive-content")
+                    .about("stores a self-contained copy of a link's page for reading offline")
+                    .arg(Arg::with_name("url")
+                         .help("the URL of the link to archive")
+                         .takes_value(true)
+                         .value_name("URL")
+                         .required(true))
+                    .arg(Arg::with_name("timeout")
+                         .help("how many seconds to wait for the page and each of its assets")
+                         .long("timeout")
+                         .takes_value(true)
+                         .value_name("SECONDS")
+                         .validator(is_whole_number)
+                         .default_value("10")))
+        .subcommand(SubCommand::with_name("open")
+                    .about("opens a link in the browser")
+                    .arg(Arg::with_name("url")
+                         .help("the URL of the link to open")
+                         .takes_value(true)
+                         .value_name("URL")
+                         .required(true))
+                    .arg(Arg::with_name("archived")
+                         .help("opens the archived copy of the page instead")
+                         .long("archived")))
+        .subcommand(SubCommand::with_name("watch-changes")
+                    .about("reports archived pages whose text has changed")
+                    .arg(Arg::with_name("query")
+                         .help("only checks links matching this keyword")
+                         .long("query")
+                         .takes_value(true)
+                         .value_name("KEYWORD"))
+                    .arg(Arg::with_name("timeout")
+                         .help("how many seconds to wait for each page")
+                         .long("timeout")
+                         .takes_value(true)
+                         .value_name("SECONDS")
+                         .validator(is_whole_number)
+                         .default_value("10"))
+                    .arg(Arg::with_name("update")
+                         .help("archives the current version of pages that have changed; the changed tag \
+                                stays until removed with tag remove")
+                         .long("update")))
+        .subcommand(SubCommand::with_name("check")
+                    .about("checks for links that no longer work")
+                    .arg(Arg::with_name("query")
+                         .help("only checks links matching this keyword")
+                         .long("query")
+                         .takes_value(true)
+                         .value_name("KEYWORD"))
+                    .arg(Arg::with_name("concurrency")
+                         .help("how many links to check at once")
+                         .long("concurrency")
+                         .takes_value(true)
+                         .value_name("N")
+                         .validator(is_whole_number)
+                         .default_value("8"))
+                    .arg(Arg::with_name("host_delay")
+                         .help("the least time between requests to the same host")
+                         .long("host-delay")
+                         .takes_value(true)
+                         .value_name("MILLISECONDS")
+                         .validator(is_whole_number)
+                         .default_value("500"))
+                    .arg(Arg::with_name("timeout")
+                         .help("how many seconds to wait for each request")
+                         .long("timeout")
+                         .takes_value(true)
+                         .value_name("SECONDS")
+                         .validator(is_whole_number)
+                         .default_value("10"))
+                    .arg(Arg::with_name("tag_broken")
+                         .help("tags links that fail as broken, and untags links that work again")
+                         .long("tag-broken"))
+                    .arg(Arg::with_name("update_redirects")
+                         .help("replaces the URLs of links that permanently redirect, keeping the old URLs \
+                                as aliases")
+                         .long("update-redirects")))
         .get_matches()
 }
 
-fn list(read_later_list: &ReadLaterList, json: bool) -> Result<(), Box<Error>> {
+fn is_whole_number(value: String) -> Result<(), String> {
+    match value.parse::<u64>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("{} is not a whole number", value)),
+    }
+}
+
+fn list(read_later_list: &ReadLaterList, args: &ArgMatches, json: bool) -> Result<(), Box<Error>> {
+    let filtered_list;
+    let read_later_list = match args.value_of("max_minutes") {
+        None => read_later_list,
+        Some(max_minutes) => {
+            let max_minutes: u64 = max_minutes.parse()?;
+            let links = read_later_list
+                .iter_links()
+                .filter(|link| link.reading_minutes.is_some_and(|minutes| minutes <= max_minutes))
+                .cloned()
+                .collect();
+            filtered_list = ReadLaterList::new().add_links(links);
+            &filtered_list
+        }
+    };
     match read_later_list.len() {
         0 => {
             if json {
@@ -165,13 +452,58 @@ fn list(read_later_list: &ReadLaterList, json: bool) -> Result<(), Box<Error>> {
     Ok(())
 }
 
-fn save(read_later_list: &mut ReadLaterList, save_args: &ArgMatches) -> Result<(), Box<Error>> {
-    let url = save_args.value_of("url").unwrap();
+fn save(
+    read_later_list: &mut ReadLaterList,
+    content_store: &ContentStore,
+    save_args: &ArgMatches,
+) -> Result<(), Box<Error>> {
+    let requested_url = save_args.value_of("url").unwrap();
+    let timeout = Duration::from_secs(save_args.value_of("timeout").unwrap().parse()?);
+    let url = match read_later_list.find_link(requested_url) {
+        Some(link_entry) => link_entry.url.clone(),
+        None if save_args.is_present("expand") => {
+            // The link is saved as given if its redirects can't be followed
+            match fetch::expand_url(requested_url, timeout) {
+                Ok(url) => read_later_list.find_link(&url).map_or(url, |link| link.url.clone()),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    String::from(requested_url)
+                }
+            }
+        }
+        None => String::from(requested_url),
+    };
+    let url = url.as_str();
+    if url != requested_url {
+        println!("{} leads to {}", requested_url, url);
+    }
     println!("Saving link {}", url);
     let old_title = match read_later_list.get_link(url) {
         None => String::from(""),
         Some(link_entry) => format!("{}", link_entry.title),
     };
+    let is_new = read_later_list.get_link(url).is_none();
+    let page = if is_new && !save_args.is_present("offline") {
+        match fetch::fetch(url, timeout) {
+            Ok(page) => Some(page),
+            Err(err) => {
+                eprintln!("{}", err);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let metadata = page.as_ref()
+        .map(|page| fetch::page_metadata(page, url))
+        .unwrap_or_default();
+    let word_count = page.as_ref()
+        .filter(|page| page.is_html())
+        .map(|page| readability::extract(&page.body, &page.url).word_count as u64);
+    let old_title = match old_title.len() {
+        0 => metadata.title.clone().unwrap_or_default(),
+        _ => old_title,
+    };
     let old_title_hint = match old_title.len() {
         0 => String::from(""),
         _ => format!(" [{}]", old_title),
@@ -230,15 +562,64 @@ fn save(read_later_list: &mut ReadLaterList, save_args: &ArgMatches) -> Result<(
         .add_tags(&mut tags.iter()
             .filter(|tag| tag.trim() != "")
             .map(|tag| tag.as_ref())
-            .collect())
-        .build()?;
+            .collect());
+    let mut link_entry = match read_later_list.get_link(url) {
+        // Keep the fields that saving doesn't ask for
+        Some(old_link) => {
+            let link_entry = link_entry.build()?;
+            LinkEntry {
+                title: link_entry.title,
+                tags: link_entry.tags,
+                ..old_link.clone()
+            }
+        }
+        None => {
+            let link_entry = match word_count {
+                Some(word_count) => link_entry.set_word_count(word_count),
+                None => link_entry,
+            };
+            set_metadata(link_entry, &metadata).set_added(now()?).build()?
+        }
+    };
+    if url != requested_url && !link_entry.aliases.iter().any(|alias| alias == requested_url) {
+        link_entry.aliases.push(String::from(requested_url));
+    }
     read_later_list.add_link(link_entry);
+    if save_args.is_present("archive") {
+        // The link is kept even if its page can't be archived
+        match archive(read_later_list, content_store, url, timeout) {
+            Ok(path) => println!("Archived {} to {}", url, path.display()),
+            Err(err) => eprintln!("{}", err),
+        }
+    }
     Ok(())
 }
 
+fn set_metadata(mut builder: LinkEntryBuilder, metadata: &Metadata) -> LinkEntryBuilder {
+    if let Some(ref description) = metadata.description {
+        builder = builder.set_description(description);
+    }
+    if let Some(ref author) = metadata.author {
+        builder = builder.set_author(author);
+    }
+    if let Some(ref site_name) = metadata.site_name {
+        builder = builder.set_site_name(site_name);
+    }
+    if let Some(ref published) = metadata.published {
+        builder = builder.set_published(published);
+    }
+    if let Some(ref canonical_url) = metadata.canonical_url {
+        builder = builder.set_canonical_url(canonical_url);
+    }
+    if let Some(ref language) = metadata.language {
+        builder = builder.set_language(language);
+    }
+    builder
+}
+
 fn show(read_later_list: &ReadLaterList, args: &ArgMatches, json: bool) -> Result<(), Box<Error>> {
     let url = args.value_of("url").unwrap();
-    match read_later_list.get_link(url) {
+    match read_later_list.find_link(url) {
         None => {
             if json {
                 println!("{}", json!({}));
@@ -257,9 +638,97 @@ fn show(read_later_list: &ReadLaterList, args: &ArgMatches, json: bool) -> Resul
     Ok(())
 }
 
-fn delete(read_later_list: &mut ReadLaterList, args: &ArgMatches) {
+fn delete(
+    read_later_list: &mut ReadLaterList,
+    content_store: &ContentStore,
+    args: &ArgMatches,
+) -> Result<(), Box<Error>> {
     let url = args.value_of("url").unwrap();
-    read_later_list.delete_link(url);
+    let url = read_later_list.find_link(url).map_or(String::from(url), |link| link.url.clone());
+    read_later_list.delete_link(&url);
+    content_store.delete(&url)?;
+    Ok(())
+}
+
+fn archive_content(
+    read_later_list: &mut ReadLaterList,
+    content_store: &ContentStore,
+    args: &ArgMatches,
+) -> Result<(), Box<Error>> {
+    let url = match read_later_list.find_link(args.value_of("url").unwrap()) {
+        Some(link) => link.url.clone(),
+        None => {
+            println!("Link {} not found", args.value_of("url").unwrap());
+            return Ok(());
+        }
+    };
+    let url = url.as_str();
+    let timeout = Duration::from_secs(args.value_of("timeout").unwrap().parse()?);
+    let path = archive(read_later_list, content_store, url, timeout)?;
+    println!("Archived {} to {}", url, path.display());
+    Ok(())
+}
+
+fn archive(
+    read_later_list: &mut ReadLaterList,
+    content_store: &ContentStore,
+    url: &str,
+    timeout: Duration,
+) -> Result<PathBuf, Box<Error>> {
+    let (fetched_url, document) = archive::snapshot_page(url, timeout)?;
+    store_snapshot(read_later_list, content_store, url, fetched_url, &document)
+}
+
+/// Stores a snapshot of a link's page along with its readable article, and records the
+/// article's length on the link.
+fn store_snapshot(
+    read_later_list: &mut ReadLaterList,
+    content_store: &ContentStore,
+    url: &str,
+    fetched_url: String,
+    document: &str,
+) -> Result<PathBuf, Box<Error>> {
+    let article = readability::extract(document, &fetched_url);
+    let info = SnapshotInfo {
+        url: String::from(url),
+        fetched_url,
+        archived: now()?,
+    };
+    let path = content_store.save_snapshot(&info, document)?;
+    content_store.save_article(url, &article)?;
+    if let Some(link) = read_later_list.get_link(url).cloned() {
+        let word_count = article.word_count as u64;
+        read_later_list.update_link(LinkEntry {
+            word_count: Some(word_count),
+            reading_minutes: Some(read_later_list::reading_minutes(word_count)),
+            ..link
+        });
+    }
+    Ok(path)
+}
+
+fn open(
+    read_later_list: &ReadLaterList,
+    content_store: &ContentStore,
+    args: &ArgMatches,
+) -> Result<(), Box<Error>> {
+    let url = match read_later_list.find_link(args.value_of("url").unwrap()) {
+        Some(link) => link.url.as_str(),
+        None => {
+            println!("Link {} not found", args.value_of("url").unwrap());
+            return Ok(());
+        }
+    };
+    if !args.is_present("archived") {
+        return Ok(open_in_browser(url)?);
+    }
+    let path = content_store.snapshot_path(url);
+    if !path.exists() {
+        println!("Link {} has not been archived; run archive-content first", url);
+        return Ok(());
+    }
+    let path = path.canonicalize()?;
+    Ok(open_in_browser(path.to_str().ok_or("Invalid snapshot path")?)?)
 }
 
 fn tag(read_later_list: &mut ReadLaterList, args: &ArgMatches) -> Result<(), Box<Error>> {
@@ -267,11 +736,11 @@ fn tag(read_later_list: &mut ReadLaterList, args: &ArgMatches) -> Result<(), Box
         ("add", Some(add_args)) => {
             let url = add_args.value_of("url").unwrap();
             let tags = add_args.values_of("tags").unwrap();
-            match read_later_list.get_link(url) {
+            match read_later_list.find_link(url).map(|link| link.url.clone()) {
                 None => println!("Link {} not found", url),
-                Some(_) => {
+                Some(url) => {
                     read_later_list.add_tags(
-                        url,
+                        &url,
                         tags.map(String::from).collect(),
                     )?;
                 }
@@ -280,11 +749,11 @@ fn tag(read_later_list: &mut ReadLaterList, args: &ArgMatches) -> Result<(), Box
         ("remove", Some(remove_args)) => {
             let url = remove_args.value_of("url").unwrap();
             let tags = remove_args.values_of("tags").unwrap();
-            match read_later_list.get_link(url) {
+            match read_later_list.find_link(url).map(|link| link.url.clone()) {
                 None => println!("Link {} not found", url),
-                Some(_) => {
+                Some(url) => {
                     read_later_list.remove_tags(
-                        url,
+                        &url,
                         tags.map(String::from).collect(),
                     )?;
                 }
@@ -297,23 +766,15 @@ fn tag(read_later_list: &mut ReadLaterList, args: &ArgMatches) -> Result<(), Box
 
 fn search(
     read_later_list: &ReadLaterList,
+    content_store: &ContentStore,
     args: &ArgMatches,
     json: bool,
 ) -> Result<(), Box<Error>> {
     let keyword = args.value_of("keyword").unwrap();
-    let re = RegexBuilder::new(&regex::escape(keyword))
-        .case_insensitive(true)
-        .build()
-        .unwrap();
-    let results = read_later_list
-        .iter_links()
-        .filter(|link_entry| {
-            re.is_match(&link_entry.url) || re.is_match(&link_entry.title) ||
-                re.is_match(&link_entry.tags.join(", "))
-        })
-        .map(|link| link.clone())
-        .collect();
-    let results_list = ReadLaterList::new().add_links(results);
+    if args.is_present("content") {
+        return search_content(read_later_list, content_store, keyword, json);
+    }
+    let results_list = read_later_list.search(keyword);
     match results_list.len() {
         0 => {
             if json {
@@ -332,3 +793,497 @@ fn search(
     }
     Ok(())
 }
+
+/// How many words of a matching article are shown with each content search result.
+const EXCERPT_LENGTH: usize = 30;
+
+fn search_content(
+    read_later_list: &ReadLaterList,
+    content_store: &ContentStore,
+    query: &str,
+    json: bool,
+) -> Result<(), Box<Error>> {
+    let highlight = |word: &str| if !json && io::stdout().is_terminal() {
+        format!("\x1b[1m{}\x1b[0m", word)
+    } else {
+        format!("**{}**", word)
+    };
+    let matches = content_store
+        .search_index()?
+        .search(query)
+        .into_iter()
+        .filter_map(|result| {
+            let link = read_later_list.get_link(&result.url)?;
+            let excerpt = content_store
+                .article(&result.url)
+                .map(|article| index::snippet(&article.text, query, EXCERPT_LENGTH, highlight))
+                .unwrap_or_default();
+            Some((link, result.score, excerpt))
+        })
+        .collect::<Vec<(&LinkEntry, f64, String)>>();
+    if json {
+        let results = matches
+            .iter()
+            .map(|result| json!({"link": result.0, "score": result.1, "excerpt": result.2}))
+            .collect::<Vec<serde_json::Value>>();
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else if matches.is_empty() {
+        println!("No results found");
+    } else {
+        let results = matches
+            .iter()
+            .map(|result| format!("{}\nexcerpt: {}", result.0, result.2))
+            .collect::<Vec<String>>();
+        println!("{}", results.join("\n---\n"));
+    }
+    Ok(())
+}
+
+fn import(
+    read_later_list: &mut ReadLaterList,
+    args: &ArgMatches,
+    json: bool,
+) -> Result<(), Box<Error>> {
+    let path = args.value_of("file").unwrap();
+    if let Some(browser) = args.value_of("from") {
+        let records = import_browser(browser, Path::new(path))?;
+        return add_imported(read_later_list, records, json);
+    }
+    let text = read_from_file(path)?;
+    let records: ImportRecords = match args.value_of("format").unwrap() {
+        "netscape" => netscape::parse(&text)?.into(),
+        "pocket" => pocket::parse(&text)?.into(),
+        "csv" => csv::parse(&text, b',', &column_mapping(args)?)?.into(),
+        "tsv" => csv::parse(&text, b'\t', &column_mapping(args)?)?.into(),
+        "opml" => opml::parse(&text)?.into(),
+        "markdown" => markdown::parse(&text)?.into(),
+        "instapaper" => instapaper::parse(&text)?,
+        "pinboard" => pinboard::parse(&text)?,
+        "bibtex" => bibtex::parse(&text)?,
+        "org" => org::parse(&text)?.into(),
+        "todotxt" => todotxt::parse(&text)?.into(),
+        format => return Err(From::from(format!("Unknown import format {}", format))),
+    };
+    add_imported(read_later_list, records, json)
+}
+
+fn import_browser(browser: &str, profile_path: &Path) -> Result<ImportRecords, Box<Error>> {
+    let file_name = match browser {
+        "firefox" => "places.sqlite",
+        "chromium" => "Bookmarks",
+        browser => return Err(From::from(format!("Unknown browser {}", browser))),
+    };
+    let path = if profile_path.is_dir() {
+        profile_path.join(file_name)
+    } else {
+        profile_path.to_path_buf()
+    };
+    match browser {
+        "firefox" => Ok(firefox::parse(&path)?),
+        _ => {
+            let text = read_from_file(path.to_str().ok_or("Invalid profile path")?)?;
+            Ok(chromium::parse(&text)?)
+        }
+    }
+}
+
+/// Adds imported links to the list. Links that are already saved keep their fields and only
+/// gain the imported tags, so that importing a list's own export doesn't lose anything.
+fn add_imported(
+    read_later_list: &mut ReadLaterList,
+    mut records: ImportRecords,
+    json: bool,
+) -> Result<(), Box<Error>> {
+    records.merge_into_existing(read_later_list);
+    let summary = ImportSummary::from_records(read_later_list, &records);
+    read_later_list.add_links(records.links);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+    } else {
+        println!("Imported links: {}", summary);
+        for skipped in &summary.skipped {
+            println!("Skipped {}", skipped);
+        }
+    }
+    Ok(())
+}
+
+fn export(
+    read_later_list: &ReadLaterList,
+    content_store: &ContentStore,
+    args: &ArgMatches,
+) -> Result<(), Box<Error>> {
+    let read_later_list = &match args.value_of("query") {
+        None => read_later_list.clone(),
+        Some(keyword) => read_later_list.search(keyword),
+    };
+    let feed_title = args.value_of("feed_title").unwrap();
+    let feed_link = args.value_of("feed_link").unwrap();
+    let out = args.value_of("out");
+    let output = match args.value_of("format").unwrap() {
+        "netscape" => {
+            let folders: Vec<String> = match args.values_of("folders") {
+                None => Vec::new(),
+                Some(folders) => folders.map(String::from).collect(),
+            };
+            netscape::render(read_later_list, &folders)
+        }
+        "csv" => csv::render(read_later_list, b',', &column_mapping(args)?)?,
+        "tsv" => csv::render(read_later_list, b'\t', &column_mapping(args)?)?,
+        "opml" => opml::render(read_later_list),
+        "atom" => feed::render_atom(read_later_list, feed_title, feed_link, now()?),
+        "rss" => feed::render_rss(read_later_list, feed_title, feed_link, now()?),
+        "markdown" => markdown::render(read_later_list, args.is_present("group_by_tag")),
+        "bibtex" => bibtex::render(read_later_list),
+        "org" => org::render(read_later_list),
+        "todotxt" => todotxt::render(read_later_list),
+        "html" => return export_site(read_later_list, args, out.unwrap()),
+        "vault" => return export_vault(read_later_list, args, out.unwrap()),
+        "epub" => return export_epub(read_later_list, content_store, args, out.unwrap()),
+        format => return Err(From::from(format!("Unknown export format {}", format))),
+    };
+    match out {
+        None => print!("{}", output),
+        Some(path) => {
+            overwrite_file(path, &output)?;
+        }
+    }
+    Ok(())
+}
+
+fn export_site(
+    read_later_list: &ReadLaterList,
+    args: &ArgMatches,
+    out_dir: &str,
+) -> Result<(), Box<Error>> {
+    let template = match args.value_of("template") {
+        None => String::from(site::DEFAULT_TEMPLATE),
+        Some(path) => read_from_file(path)?,
+    };
+    let pages = site::render(read_later_list, "Read-later list", &template);
+    for page in &pages {
+        let path = Path::new(out_dir).join(&page.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        overwrite_file(path.to_str().ok_or("Invalid output path")?, &page.contents)?;
+    }
+    println!("Wrote {} pages to {}", pages.len(), out_dir);
+    Ok(())
+}
+
+fn export_vault(
+    read_later_list: &ReadLaterList,
+    args: &ArgMatches,
+    out_dir: &str,
+) -> Result<(), Box<Error>> {
+    fs::create_dir_all(out_dir)?;
+    let notes = vault::render(read_later_list);
+    let mut unchanged = 0;
+    for note in &notes {
+        let path = Path::new(out_dir).join(&note.path);
+        let path = path.to_str().ok_or("Invalid output path")?;
+        let existing = match read_from_file(path) {
+            Ok(existing) if args.is_present("incremental") => existing,
+            _ => {
+                overwrite_file(path, &note.contents)?;
+                continue;
+            }
+        };
+        match vault::update_note(&existing, &note.contents) {
+            Some(updated) => {
+                overwrite_file(path, &updated)?;
+            }
+            None => unchanged += 1,
+        }
+    }
+    println!(
+        "Wrote {} notes to {} ({} unchanged)",
+        notes.len() - unchanged,
+        out_dir,
+        unchanged
+    );
+
+    // Notes written by an earlier export whose links have since been deleted or renamed
+    let paths = notes.iter().map(|note| note.path.as_str()).collect::<Vec<&str>>();
+    let mut stale = Vec::new();
+    for entry in fs::read_dir(out_dir)? {
+        let path = entry?.path();
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        if !name.ends_with(".md") || paths.contains(&name) {
+            continue;
+        }
+        let contents = fs::read_to_string(&path).unwrap_or_default();
+        if vault::note_hash(&contents).is_some() {
+            stale.push(path);
+        }
+    }
+    stale.sort();
+    for path in &stale {
+        if args.is_present("prune") {
+            fs::remove_file(path)?;
+            println!("Removed {}", path.display());
+        } else {
+            println!("{} is for a link that is no longer in the list", path.display());
+        }
+    }
+    if !stale.is_empty() && !args.is_present("prune") {
+        println!("Run the export with --prune to remove these notes");
+    }
+    Ok(())
+}
+
+fn export_epub(
+    read_later_list: &ReadLaterList,
+    content_store: &ContentStore,
+    args: &ArgMatches,
+    out: &str,
+) -> Result<(), Box<Error>> {
+    let order = args.value_of("order").unwrap().parse::<epub::Order>()?;
+    let book = epub::render(
+        read_later_list,
+        args.value_of("book_title").unwrap(),
+        order,
+        now()?,
+        |link| readable_content(content_store, &link.url),
+    )?;
+    fs::write(out, book)?;
+    println!("Wrote {} chapters to {}", read_later_list.len(), out);
+    Ok(())
+}
+
+/// Returns the readable article of an archived link, extracting it from the snapshot if it
+/// wasn't stored when the link was archived.
+fn readable_content(content_store: &ContentStore, url: &str) -> Option<String> {
+    stored_article(content_store, url).map(|article| article.content)
+}
+
+/// Returns the readable article of a link's snapshot, extracting it if it wasn't stored.
+fn stored_article(content_store: &ContentStore, url: &str) -> Option<Article> {
+    if let Some(article) = content_store.article(url) {
+        return Some(article);
+    }
+    let info = content_store.snapshot_info(url)?;
+    Some(readability::extract(&content_store.snapshot(url)?, &info.fetched_url))
+}
+
+/// How many unchanged lines are shown around each change to a page.
+const DIFF_CONTEXT: usize = 2;
+
+fn watch_changes(
+    read_later_list: &mut ReadLaterList,
+    content_store: &ContentStore,
+    args: &ArgMatches,
+    json: bool,
+) -> Result<(), Box<Error>> {
+    let timeout = Duration::from_secs(args.value_of("timeout").unwrap().parse()?);
+    let watched = match args.value_of("query") {
+        None => read_later_list.clone(),
+        Some(keyword) => read_later_list.search(keyword),
+    };
+    let mut urls = watched
+        .iter_links()
+        .map(|link| link.url.clone())
+        .filter(|url| content_store.snapshot_info(url).is_some())
+        .collect::<Vec<String>>();
+    urls.sort();
+    let mut changes = Vec::new();
+    for url in &urls {
+        let old_article = match stored_article(content_store, url) {
+            Some(article) => article,
+            None => continue,
+        };
+        let page = match fetch::fetch(url, timeout) {
+            Ok(ref page) if !page.is_html() => {
+                eprintln!("{} is not an HTML page ({})", url, page.content_type);
+                continue;
+            }
+            Ok(page) => page,
+            Err(err) => {
+                eprintln!("{}", err);
+                continue;
+            }
+        };
+        // Extract the text from the page as it would be archived, without fetching its assets
+        let document = archive::inline_assets(&page.body, &page.url, |_| None);
+        let new_article = readability::extract(&document, &page.url);
+        let diff = diff::unified_diff(&old_article.text, &new_article.text, DIFF_CONTEXT);
+        if diff.is_empty() {
+            continue;
+        }
+        read_later_list.add_tags(url, vec![String::from("changed")])?;
+        if args.is_present("update") {
+            let snapshot = archive::snapshot_fetched_page(&page, timeout)
+                .map_err(From::from)
+                .and_then(|document| {
+                    store_snapshot(read_later_list, content_store, url, page.url, &document)
+                });
+            if let Err(err) = snapshot {
+                eprintln!("{}", err);
+            }
+        }
+        changes.push((url, diff));
+    }
+    if json {
+        let results = changes
+            .iter()
+            .map(|change| json!({"url": change.0, "diff": change.1}))
+            .collect::<Vec<serde_json::Value>>();
+        println!("{}", serde_json::to_string_pretty(&results)?);
+        return Ok(());
+    }
+    for change in &changes {
+        println!("Changed: {}\n{}", change.0, change.1);
+    }
+    println!("Checked {} archived links: {} changed", urls.len(), changes.len());
+    Ok(())
+}
+
+fn check(
+    read_later_list: &mut ReadLaterList,
+    content_store: &ContentStore,
+    args: &ArgMatches,
+    json: bool,
+) -> Result<(), Box<Error>> {
+    let checked = match args.value_of("query") {
+        None => read_later_list.clone(),
+        Some(keyword) => read_later_list.search(keyword),
+    };
+    let mut urls = checked
+        .iter_links()
+        .map(|link| link.url.clone())
+        .collect::<Vec<String>>();
+    urls.sort();
+    let options = CheckOptions {
+        concurrency: args.value_of("concurrency").unwrap().parse()?,
+        host_interval: Duration::from_millis(args.value_of("host_delay").unwrap().parse()?),
+        timeout: Duration::from_secs(args.value_of("timeout").unwrap().parse()?),
+        ..CheckOptions::default()
+    };
+    let results = check::check_all(&urls, &options);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        for result in results
+            .iter()
+            .filter(|result| !matches!(result.outcome, Outcome::Ok { .. }))
+        {
+            println!("{}: {}", result.url, result.outcome);
+        }
+        let broken = results.iter().filter(|result| result.outcome.is_broken()).count();
+        let redirected = results
+            .iter()
+            .filter(|result| matches!(result.outcome, Outcome::Redirected { .. }))
+            .count();
+        println!(
+            "Checked {} links: {} ok, {} redirected, {} broken",
+            results.len(),
+            results.len() - broken - redirected,
+            redirected,
+            broken
+        );
+    }
+    // Keep stdout parseable when it holds the JSON results
+    let report = |message: String| if json {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    };
+    for result in &results {
+        let broken_tag = vec![String::from("broken")];
+        if args.is_present("tag_broken") {
+            if result.outcome.is_broken() {
+                read_later_list.add_tags(&result.url, broken_tag)?;
+            } else {
+                read_later_list.remove_tags(&result.url, broken_tag)?;
+            }
+        }
+        if let Outcome::Redirected {
+            ref to,
+            permanent: true,
+            ..
+        } = result.outcome
+        {
+            if !args.is_present("update_redirects") {
+                continue;
+            }
+            if read_later_list.find_link(to).is_some() {
+                report(format!("Not updating {}: {} is already saved", result.url, to));
+                continue;
+            }
+            let link = read_later_list.get_link(&result.url).unwrap().clone();
+            // The old URL stays findable as an alias of the updated link
+            let mut aliases = link.aliases.clone();
+            aliases.retain(|alias| alias != to && alias != &result.url);
+            aliases.push(result.url.clone());
+            read_later_list.delete_link(&result.url);
+            read_later_list.add_link(LinkEntry {
+                url: to.clone(),
+                aliases,
+                ..link
+            });
+            content_store.rename(&result.url, to)?;
+            report(format!("Updated {} to {}", result.url, to));
+        }
+    }
+    Ok(())
+}
+
+fn column_mapping(args: &ArgMatches) -> Result<ColumnMapping, String> {
+    match args.values_of("columns") {
+        None => Ok(ColumnMapping::new()),
+        Some(columns) => ColumnMapping::parse(&columns.collect::<Vec<&str>>()),
+    }
+}
+
+fn now() -> Result<u64, Box<Error>> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+}
+
+fn sync(
+    read_later_list: &ReadLaterList,
+    list_file_path: &str,
+    args: &ArgMatches,
+) -> Result<ReadLaterList, Box<Error>> {
+    let state_file_path = args.value_of("state_file").unwrap();
+    let replica_file_path = format!("{}.replica", list_file_path);
+    let mut replica = match read_from_file(&replica_file_path) {
+        Ok(text) => ReplicatedList::parse(&text)?,
+        Err(_) => {
+            let replica_id = match args.value_of("replica") {
+                Some(replica_id) => String::from(replica_id),
+                None => crdt::random_replica_id(),
+            };
+            ReplicatedList::new(&replica_id)
+        }
+    };
+    let shared = match read_from_file(state_file_path) {
+        Ok(text) => Some(ReplicatedList::parse(&text)?),
+        Err(_) => None,
+    };
+    if let Some(ref shared) = shared {
+        if replica.conflicts_with(shared) {
+            return Err(From::from(format!(
+                "{} has changes from another device that uses the replica ID {}; remove {} \
+                 and sync again with a different --replica",
+                state_file_path,
+                replica.replica(),
+                replica_file_path
+            )));
+        }
+    }
+    replica.record(read_later_list);
+    if let Some(ref shared) = shared {
+        replica.merge(shared);
+    }
+    let state = replica.to_state_string()?;
+    overwrite_file(&replica_file_path, &state)?;
+    overwrite_file(state_file_path, &state)?;
+    let merged_list = replica.to_read_later_list();
+    println!(
+        "Synced replica {}: {} links",
+        replica.replica(),
+        merged_list.len()
+    );
+    Ok(merged_list)
+}
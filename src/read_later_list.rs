@@ -4,19 +4,65 @@ use std::vec::Vec;
 use std::string::String;
 use std::collections::HashMap;
 use std::collections::hash_map::Values;
-use regex::Regex;
+use regex::{self, Regex, RegexBuilder};
+use util::collapse_whitespace;
 
-#[derive(Debug, Clone, PartialEq, Serialize)]
+/// The reading speed that reading time estimates assume.
+const WORDS_PER_MINUTE: u64 = 200;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LinkEntry {
     pub url: String,
     pub title: String,
     pub tags: Vec<String>,
+    /// When the link was saved, in seconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub added: Option<u64>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub read: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site_name: Option<String>,
+    /// When the page was published, as given by the page (usually an ISO 8601 date)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub published: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Other URLs that lead to this link, such as the short link it was saved from
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// How many words the page's article has
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_count: Option<u64>,
+    /// About how many minutes the page's article takes to read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reading_minutes: Option<u64>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
 }
 
 pub struct LinkEntryBuilder {
     url: Option<String>,
     title: Option<String>,
     tags: Vec<String>,
+    added: Option<u64>,
+    read: bool,
+    description: Option<String>,
+    author: Option<String>,
+    site_name: Option<String>,
+    published: Option<String>,
+    canonical_url: Option<String>,
+    language: Option<String>,
+    aliases: Vec<String>,
+    word_count: Option<u64>,
+    reading_minutes: Option<u64>,
 }
 
 impl LinkEntryBuilder {
@@ -25,6 +71,17 @@ impl LinkEntryBuilder {
             url: None,
             title: None,
             tags: Vec::new(),
+            added: None,
+            read: false,
+            description: None,
+            author: None,
+            site_name: None,
+            published: None,
+            canonical_url: None,
+            language: None,
+            aliases: Vec::new(),
+            word_count: None,
+            reading_minutes: None,
         }
     }
 
@@ -50,6 +107,67 @@ impl LinkEntryBuilder {
         self
     }
 
+    pub fn set_added(mut self, added: u64) -> LinkEntryBuilder {
+        self.added = Some(added);
+        self
+    }
+
+    pub fn set_read(mut self, read: bool) -> LinkEntryBuilder {
+        self.read = read;
+        self
+    }
+
+    pub fn set_description(mut self, description: &str) -> LinkEntryBuilder {
+        self.description = metadata_value(description);
+        self
+    }
+
+    pub fn set_author(mut self, author: &str) -> LinkEntryBuilder {
+        self.author = metadata_value(author);
+        self
+    }
+
+    pub fn set_site_name(mut self, site_name: &str) -> LinkEntryBuilder {
+        self.site_name = metadata_value(site_name);
+        self
+    }
+
+    pub fn set_published(mut self, published: &str) -> LinkEntryBuilder {
+        self.published = metadata_value(published);
+        self
+    }
+
+    pub fn set_canonical_url(mut self, canonical_url: &str) -> LinkEntryBuilder {
+        self.canonical_url = metadata_value(canonical_url);
+        self
+    }
+
+    pub fn set_language(mut self, language: &str) -> LinkEntryBuilder {
+        self.language = metadata_value(language);
+        self
+    }
+
+    pub fn add_alias(mut self, alias: &str) -> LinkEntryBuilder {
+        if !self.aliases.iter().any(|existing| existing == alias) {
+            self.aliases.push(String::from(alias));
+        }
+        self
+    }
+
+    /// Sets the word count, and the reading time derived from it unless one is already set.
+    pub fn set_word_count(mut self, word_count: u64) -> LinkEntryBuilder {
+        self.word_count = Some(word_count);
+        if self.reading_minutes.is_none() {
+            self.reading_minutes = Some(reading_minutes(word_count));
+        }
+        self
+    }
+
+    pub fn set_reading_minutes(mut self, reading_minutes: u64) -> LinkEntryBuilder {
+        self.reading_minutes = Some(reading_minutes);
+        self
+    }
+
     pub fn build(self) -> Result<LinkEntry, String> {
         match self.url {
             None => Err(String::from("URL not set")),
@@ -62,11 +180,33 @@ impl LinkEntryBuilder {
                                 url: url,
                                 title: title,
                                 tags: Vec::new(),
+                                added: self.added,
+                                read: self.read,
+                                description: self.description,
+                                author: self.author,
+                                site_name: self.site_name,
+                                published: self.published,
+                                canonical_url: self.canonical_url,
+                                language: self.language,
+                                aliases: self.aliases,
+                                word_count: self.word_count,
+                                reading_minutes: self.reading_minutes,
                             }),
                             _ => Ok(LinkEntry {
                                 url: url,
                                 title: title,
                                 tags: self.tags,
+                                added: self.added,
+                                read: self.read,
+                                description: self.description,
+                                author: self.author,
+                                site_name: self.site_name,
+                                published: self.published,
+                                canonical_url: self.canonical_url,
+                                language: self.language,
+                                aliases: self.aliases,
+                                word_count: self.word_count,
+                                reading_minutes: self.reading_minutes,
                             }),
                         }
                     }
@@ -76,11 +216,40 @@ impl LinkEntryBuilder {
     }
 }
 
+/// Estimates how many minutes it takes to read a number of words, rounding up.
+pub fn reading_minutes(word_count: u64) -> u64 {
+    word_count.div_ceil(WORDS_PER_MINUTE)
+}
+
+/// Collapses a metadata value onto one line, treating a blank value as missing.
+fn metadata_value(value: &str) -> Option<String> {
+    let value = collapse_whitespace(value);
+    if value.is_empty() {
+        None
+    } else {
+        Some(value)
+    }
+}
+
 impl LinkEntry {
     pub fn builder() -> LinkEntryBuilder {
         LinkEntryBuilder::new()
     }
 
+    /// Returns the names and values of the page metadata fields that are set.
+    pub fn metadata(&self) -> Vec<(&'static str, &str)> {
+        vec![
+            ("description", &self.description),
+            ("author", &self.author),
+            ("site_name", &self.site_name),
+            ("published", &self.published),
+            ("canonical_url", &self.canonical_url),
+            ("language", &self.language),
+        ].into_iter()
+            .filter_map(|field| field.1.as_ref().map(|value| (field.0, value.as_ref())))
+            .collect()
+    }
+
     fn parse(text: &str) -> Result<LinkEntry, String> {
         lazy_static! {
             static ref RE: Regex = Regex::new(r"^(.+?)\s*:\s*(.+)$").unwrap();
@@ -101,6 +270,38 @@ impl LinkEntry {
                                 .map(|s| s.trim())
                                 .collect::<Vec<&str>>())
                         }
+                        "added" => {
+                            match cap[2].trim().parse() {
+                                Ok(added) => builder.set_added(added),
+                                Err(_) => builder,
+                            }
+                        }
+                        "read" => builder.set_read(cap[2].trim() == "true"),
+                        "word_count" => {
+                            match cap[2].trim().parse() {
+                                Ok(word_count) => builder.set_word_count(word_count),
+                                Err(_) => builder,
+                            }
+                        }
+                        "reading_minutes" => {
+                            match cap[2].trim().parse() {
+                                Ok(reading_minutes) => builder.set_reading_minutes(reading_minutes),
+                                Err(_) => builder,
+                            }
+                        }
+                        "description" => builder.set_description(&cap[2]),
+                        "author" => builder.set_author(&cap[2]),
+                        "site_name" => builder.set_site_name(&cap[2]),
+                        "published" => builder.set_published(&cap[2]),
+                        "canonical_url" => builder.set_canonical_url(&cap[2]),
+                        "language" => builder.set_language(&cap[2]),
+                        "aliases" => {
+                            cap[2]
+                                .split(", ")
+                                .map(|alias| alias.trim())
+                                .filter(|alias| !alias.is_empty())
+                                .fold(builder, |builder, alias| builder.add_alias(alias))
+                        }
                         _ => builder,
                     }
                 }
@@ -113,13 +314,34 @@ impl fmt::Display for LinkEntry {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "url: {}\ntitle: {}{}",
+            "url: {}\ntitle: {}{}{}{}{}{}{}{}",
             self.url,
             self.title,
             match self.tags.len() {
                 0 => String::from(""),
                 _ => String::from("\ntags: ") + &self.tags.join(", "),
-            }
+            },
+            match self.added {
+                None => String::from(""),
+                Some(added) => format!("\nadded: {}", added),
+            },
+            if self.read { "\nread: true" } else { "" },
+            match self.aliases.len() {
+                0 => String::from(""),
+                _ => String::from("\naliases: ") + &self.aliases.join(", "),
+            },
+            match self.word_count {
+                None => String::from(""),
+                Some(word_count) => format!("\nword_count: {}", word_count),
+            },
+            match self.reading_minutes {
+                None => String::from(""),
+                Some(reading_minutes) => format!("\nreading_minutes: {}", reading_minutes),
+            },
+            self.metadata()
+                .iter()
+                .map(|field| format!("\n{}: {}", field.0, field.1))
+                .collect::<String>()
         )
     }
 }
@@ -180,6 +402,14 @@ impl ReadLaterList {
         self.links.get(url)
     }
 
+    /// Finds the link saved under `url`, or the link that has `url` as an alias.
+    pub fn find_link(&self, url: &str) -> Option<&LinkEntry> {
+        self.get_link(url).or_else(|| {
+            self.iter_links()
+                .find(|link| link.aliases.iter().any(|alias| alias == url))
+        })
+    }
+
     pub fn update_link(&mut self, new_link: LinkEntry) -> ReadLaterList {
         self.links.insert(new_link.url.clone(), new_link);
         self.clone()
@@ -190,21 +420,33 @@ impl ReadLaterList {
         self.clone()
     }
 
+    /// Returns the links whose URL, title or tags contain `keyword`, ignoring case.
+    pub fn search(&self, keyword: &str) -> ReadLaterList {
+        let re = RegexBuilder::new(&regex::escape(keyword))
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        let results = self.iter_links()
+            .filter(|link_entry| {
+                re.is_match(&link_entry.url) || re.is_match(&link_entry.title) ||
+                    re.is_match(&link_entry.tags.join(", "))
+            })
+            .cloned()
+            .collect();
+        ReadLaterList::new().add_links(results)
+    }
+
     pub fn add_tags(&mut self, url: &str, tags: Vec<String>) -> Result<ReadLaterList, String> {
         match self.clone().links.get(url) {
             None => return Err(format!("Link {} does not exist", url)),
             Some(link_entry) => {
-                let new_link = LinkEntryBuilder::new()
-                    .set_title(&link_entry.title)
-                    .set_url(&link_entry.url)
-                    .add_tags(&mut link_entry
-                        .tags
-                        .iter()
-                        .chain(tags.iter().filter(|tag| !link_entry.tags.contains(tag)))
-                        .map(|tag| tag.as_ref())
-                        .collect::<Vec<&str>>())
-                    .build()
-                    .unwrap();
+                let mut new_link = link_entry.clone();
+                new_link.tags = link_entry
+                    .tags
+                    .iter()
+                    .chain(tags.iter().filter(|tag| !link_entry.tags.contains(tag)))
+                    .cloned()
+                    .collect();
                 Ok(self.update_link(new_link))
             }
         }
@@ -214,17 +456,13 @@ impl ReadLaterList {
         match self.clone().links.get(url) {
             None => return Err(format!("Link {} does not exist", url)),
             Some(link_entry) => {
-                let new_link = LinkEntryBuilder::new()
-                    .set_title(&link_entry.title)
-                    .set_url(&link_entry.url)
-                    .add_tags(&mut link_entry
-                        .tags
-                        .iter()
-                        .filter(|tag| !tags.contains(tag))
-                        .map(|tag| tag.as_ref())
-                        .collect::<Vec<&str>>())
-                    .build()
-                    .unwrap();
+                let mut new_link = link_entry.clone();
+                new_link.tags = link_entry
+                    .tags
+                    .iter()
+                    .filter(|tag| !tags.contains(tag))
+                    .cloned()
+                    .collect();
                 Ok(self.update_link(new_link))
             }
         }
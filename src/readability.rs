@@ -0,0 +1,458 @@
+use std::collections::HashMap;
+use std::string::String;
+use std::vec::Vec;
+use regex::Regex;
+use fetch;
+use html::{self, Tag, Token};
+use util::collapse_whitespace;
+
+/// The main content of a web page, with the page's clutter removed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Article {
+    pub title: Option<String>,
+    pub byline: Option<String>,
+    /// The article as sanitized HTML
+    pub content: String,
+    /// The article as plain text, with a blank line between paragraphs
+    pub text: String,
+    pub word_count: usize,
+}
+
+/// Elements that never have content or a closing tag.
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements that close an open paragraph, and that separate paragraphs of text.
+const BLOCK_ELEMENTS: [&str; 29] = [
+    "address", "article", "aside", "blockquote", "dd", "div", "dl", "dt", "figcaption", "figure",
+    "footer", "form", "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr", "li", "main", "nav",
+    "ol", "p", "pre", "section", "table", "ul",
+];
+
+/// Elements that are never part of an article.
+const UNLIKELY_ELEMENTS: [&str; 16] = [
+    "script", "style", "noscript", "template", "nav", "header", "footer", "aside", "form",
+    "iframe", "svg", "button", "select", "input", "textarea", "object",
+];
+
+/// Elements whose text is scored as a paragraph of the article.
+const SCORED_ELEMENTS: [&str; 4] = ["p", "pre", "td", "blockquote"];
+
+enum Kind {
+    Element(Tag),
+    Text(String),
+}
+
+struct Node {
+    kind: Kind,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+/// A document tree built from tokens, forgiving of unclosed and misnested tags.
+struct Document {
+    nodes: Vec<Node>,
+}
+
+impl Document {
+    fn parse(document: &str) -> Document {
+        let root = Node {
+            kind: Kind::Element(Tag {
+                name: String::from("#root"),
+                attributes: Vec::new(),
+                self_closing: false,
+            }),
+            parent: None,
+            children: Vec::new(),
+        };
+        let mut tree = Document { nodes: vec![root] };
+        let mut open = vec![0];
+        for token in html::tokenize(document) {
+            match token {
+                Token::Start(tag) => {
+                    let closes_paragraph = BLOCK_ELEMENTS.contains(&tag.name.as_ref());
+                    let current = *open.last().unwrap();
+                    if (closes_paragraph && tree.name(current) == "p") ||
+                        (tag.name == "li" && tree.name(current) == "li")
+                    {
+                        open.pop();
+                    }
+                    let is_container = !tag.self_closing &&
+                        !VOID_ELEMENTS.contains(&tag.name.as_ref());
+                    let id = tree.push(*open.last().unwrap(), Kind::Element(tag));
+                    if is_container {
+                        open.push(id);
+                    }
+                }
+                Token::End(name) => {
+                    if let Some(index) = open.iter().rposition(|id| tree.name(*id) == name) {
+                        if index > 0 {
+                            open.truncate(index);
+                        }
+                    }
+                }
+                Token::Text(text) => {
+                    tree.push(*open.last().unwrap(), Kind::Text(text));
+                }
+            }
+        }
+        tree
+    }
+
+    fn push(&mut self, parent: usize, kind: Kind) -> usize {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            kind,
+            parent: Some(parent),
+            children: Vec::new(),
+        });
+        self.nodes[parent].children.push(id);
+        id
+    }
+
+    fn tag(&self, id: usize) -> Option<&Tag> {
+        match self.nodes[id].kind {
+            Kind::Element(ref tag) => Some(tag),
+            Kind::Text(_) => None,
+        }
+    }
+
+    fn name(&self, id: usize) -> &str {
+        self.tag(id).map(|tag| tag.name.as_ref()).unwrap_or("")
+    }
+
+    fn text(&self, id: usize) -> String {
+        let mut text = String::new();
+        self.collect_text(id, &mut text);
+        text
+    }
+
+    fn collect_text(&self, id: usize, text: &mut String) {
+        match self.nodes[id].kind {
+            Kind::Text(ref content) => text.push_str(content),
+            Kind::Element(_) => {
+                for child in &self.nodes[id].children {
+                    self.collect_text(*child, text);
+                }
+            }
+        }
+    }
+
+    /// Returns the elements below `id` in document order, including `id` itself.
+    fn elements(&self, id: usize) -> Vec<usize> {
+        let mut elements = Vec::new();
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            if self.tag(id).is_some() {
+                elements.push(id);
+                pending.extend(self.nodes[id].children.iter().rev());
+            }
+        }
+        elements
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.elements(0).into_iter().find(|id| self.name(*id) == name)
+    }
+
+    /// Returns the share of an element's text that is inside links.
+    fn link_density(&self, id: usize) -> f64 {
+        let length = text_length(&self.text(id));
+        if length == 0 {
+            return 0.0;
+        }
+        let link_length = self.elements(id)
+            .into_iter()
+            .filter(|element| self.name(*element) == "a")
+            .map(|element| text_length(&self.text(element)))
+            .sum::<usize>();
+        link_length as f64 / length as f64
+    }
+
+    fn serialize(&self, id: usize, output: &mut Vec<Token>) {
+        match self.nodes[id].kind {
+            Kind::Text(ref text) => output.push(Token::Text(text.clone())),
+            Kind::Element(ref tag) => {
+                output.push(Token::Start(tag.clone()));
+                for child in &self.nodes[id].children {
+                    self.serialize(*child, output);
+                }
+                if !tag.self_closing && !VOID_ELEMENTS.contains(&tag.name.as_ref()) {
+                    output.push(Token::End(tag.name.clone()));
+                }
+            }
+        }
+    }
+
+    fn is_attached(&self, id: usize) -> bool {
+        let mut current = id;
+        while let Some(parent) = self.nodes[current].parent {
+            current = parent;
+        }
+        current == 0
+    }
+
+    /// Detaches an element from the tree.
+    fn remove(&mut self, id: usize) {
+        if let Some(parent) = self.nodes[id].parent.take() {
+            self.nodes[parent].children.retain(|child| *child != id);
+        }
+    }
+}
+
+fn text_length(text: &str) -> usize {
+    text.split_whitespace().map(|word| word.chars().count() + 1).sum()
+}
+
+/// Extracts the article from an HTML document served from `url`.
+///
+/// This follows the approach of Mozilla's Readability: after removing elements that are
+/// unlikely to be content, each paragraph adds a score based on its length to its parent and
+/// grandparent, and the best-scoring element together with its related siblings is taken as
+/// the article.
+pub fn extract(document: &str, url: &str) -> Article {
+    lazy_static! {
+        static ref UNLIKELY_RE: Regex = Regex::new(r"(?i)-ad-|ad-break|adbox|advert|agegate|banner|breadcrumb|combx|comment|community|cookie|disqus|extra|foot|gdpr|header|legends|menu|modal|newsletter|pager|pagination|popup|promo|related|remark|replies|rss|share|shoutbox|sidebar|skyscraper|social|sponsor|subscribe|supplemental|tags|toolbar|widget").unwrap();
+        static ref MAYBE_RE: Regex = Regex::new(r"(?i)and|article|body|column|content|main|shadow|story|text|entry|post").unwrap();
+        static ref BYLINE_RE: Regex = Regex::new(r"(?i)byline|author|dateline|writtenby|p-author").unwrap();
+    }
+    let metadata = fetch::extract_metadata(document, url);
+    let mut tree = Document::parse(document);
+
+    let mut byline = metadata.author.clone();
+    for id in tree.elements(0) {
+        if !tree.is_attached(id) {
+            continue;
+        }
+        let name = String::from(tree.name(id));
+        let class_and_id = class_and_id(&tree, id);
+        if UNLIKELY_ELEMENTS.contains(&name.as_ref()) {
+            tree.remove(id);
+            continue;
+        }
+        if byline.is_none() && (BYLINE_RE.is_match(&class_and_id) ||
+            tree.tag(id).and_then(|tag| tag.attribute("rel")) == Some("author"))
+        {
+            let text = collapse_whitespace(&tree.text(id));
+            if !text.is_empty() && text.len() < 100 {
+                byline = Some(text);
+                tree.remove(id);
+                continue;
+            }
+        }
+        let is_structural = ["html", "body", "article", "main"].contains(&name.as_ref());
+        if !is_structural && UNLIKELY_RE.is_match(&class_and_id) &&
+            !MAYBE_RE.is_match(&class_and_id)
+        {
+            tree.remove(id);
+        }
+    }
+
+    // Score the elements containing paragraphs
+    let body = tree.find("body").unwrap_or(0);
+    let mut scores: HashMap<usize, f64> = HashMap::new();
+    for id in tree.elements(body) {
+        let name = tree.name(id);
+        let is_paragraph = SCORED_ELEMENTS.contains(&name) ||
+            (name == "div" &&
+                !tree.nodes[id]
+                    .children
+                    .iter()
+                    .any(|child| BLOCK_ELEMENTS.contains(&tree.name(*child))));
+        if !is_paragraph {
+            continue;
+        }
+        let text = tree.text(id);
+        let length = text_length(&text);
+        if length < 25 {
+            continue;
+        }
+        let score = 1.0 + text.matches(',').count() as f64 + (length as f64 / 100.0).min(3.0);
+        let mut ancestor = tree.nodes[id].parent;
+        let mut level = 0;
+        while let Some(parent) = ancestor {
+            if level == 3 || tree.name(parent) == "#root" {
+                break;
+            }
+            let initial = initial_score(&tree, parent);
+            let divider = match level {
+                0 => 1.0,
+                1 => 2.0,
+                _ => level as f64 * 3.0,
+            };
+            *scores.entry(parent).or_insert(initial) += score / divider;
+            ancestor = tree.nodes[parent].parent;
+            level += 1;
+        }
+    }
+    let scored = scores
+        .iter()
+        .map(|(id, score)| (*id, score * (1.0 - tree.link_density(*id))))
+        .collect::<HashMap<usize, f64>>();
+    let top = scored
+        .iter()
+        .fold(None, |best: Option<(usize, f64)>, (id, score)| match best {
+            Some(best) if best.1 > *score || (best.1 == *score && best.0 < *id) => Some(best),
+            _ => Some((*id, *score)),
+        });
+
+    // Take the top element along with any siblings that look like part of the article
+    let mut parts = Vec::new();
+    match top {
+        None => parts.push(body),
+        Some((top, top_score)) => {
+            let threshold = (top_score * 0.2).max(10.0);
+            let siblings = match tree.nodes[top].parent {
+                Some(parent) => tree.nodes[parent].children.clone(),
+                None => vec![top],
+            };
+            for sibling in siblings {
+                let include = sibling == top ||
+                    scored.get(&sibling).map(|score| *score >= threshold).unwrap_or(false) ||
+                    (tree.name(sibling) == "p" && {
+                        let text = tree.text(sibling);
+                        let length = text_length(&text);
+                        let density = tree.link_density(sibling);
+                        (length > 80 && density < 0.25) ||
+                            (length > 0 && density == 0.0 && text.contains(". "))
+                    });
+                if include {
+                    parts.push(sibling);
+                }
+            }
+        }
+    }
+    let mut tokens = Vec::new();
+    for part in parts {
+        tree.serialize(part, &mut tokens);
+    }
+    let title = article_title(&tree, metadata.title);
+    let content = html::sanitize(&html::serialize(&remove_title_heading(tokens, &title)));
+    let text = plain_text(&content);
+    Article {
+        title,
+        byline,
+        word_count: text.split_whitespace().count(),
+        content,
+        text,
+    }
+}
+
+fn class_and_id(tree: &Document, id: usize) -> String {
+    match tree.tag(id) {
+        None => String::new(),
+        Some(tag) => format!(
+            "{} {}",
+            tag.attribute("class").unwrap_or(""),
+            tag.attribute("id").unwrap_or("")
+        ),
+    }
+}
+
+fn initial_score(tree: &Document, id: usize) -> f64 {
+    lazy_static! {
+        static ref POSITIVE_RE: Regex = Regex::new(r"(?i)article|body|content|entry|hentry|h-entry|main|page|pagination|post|text|blog|story").unwrap();
+        static ref NEGATIVE_RE: Regex = Regex::new(r"(?i)-ad-|hidden|^hid$| hid$| hid |^hid |banner|combx|comment|com-|contact|foot|footer|footnote|gdpr|masthead|media|meta|outbrain|promo|related|scroll|share|shoutbox|sidebar|skyscraper|sponsor|shopping|tags|tool|widget").unwrap();
+    }
+    let tag_score = match tree.name(id) {
+        "article" => 10.0,
+        "div" | "main" | "section" => 5.0,
+        "pre" | "td" | "blockquote" => 3.0,
+        "address" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+        _ => 0.0,
+    };
+    let class_and_id = class_and_id(tree, id);
+    let mut class_score = 0.0;
+    if POSITIVE_RE.is_match(&class_and_id) {
+        class_score += 25.0;
+    }
+    if NEGATIVE_RE.is_match(&class_and_id) {
+        class_score -= 25.0;
+    }
+    tag_score + class_score
+}
+
+/// Picks the article's title, preferring a heading that matches the page title, and otherwise
+/// removing a site name appended to the page title.
+fn article_title(tree: &Document, page_title: Option<String>) -> Option<String> {
+    lazy_static! {
+        static ref SEPARATOR_RE: Regex = Regex::new(r"\s+[|\-\u{2013}\u{2014}\u{b7}/>\u{bb}]\s+").unwrap();
+    }
+    let page_title = page_title?;
+    let headings = tree.elements(0)
+        .into_iter()
+        .filter(|id| tree.name(*id) == "h1" || tree.name(*id) == "h2")
+        .map(|id| collapse_whitespace(&tree.text(id)))
+        .collect::<Vec<String>>();
+    if let Some(heading) = headings
+        .iter()
+        .find(|heading| heading.len() > 10 && page_title.contains(heading.as_str()))
+    {
+        return Some(heading.clone());
+    }
+    let parts = SEPARATOR_RE.split(&page_title).collect::<Vec<&str>>();
+    if parts.len() > 1 {
+        let title = parts[..parts.len() - 1].join(" ");
+        if title.split_whitespace().count() >= 3 {
+            return Some(title);
+        }
+    }
+    Some(page_title)
+}
+
+/// Drops a heading that only repeats the article's title.
+fn remove_title_heading(tokens: Vec<Token>, title: &Option<String>) -> Vec<Token> {
+    let title = match *title {
+        Some(ref title) => title,
+        None => return tokens,
+    };
+    let heading = tokens.iter().enumerate().find_map(|(index, token)| match *token {
+        Token::Start(ref tag) if tag.name == "h1" || tag.name == "h2" => Some(index),
+        _ => None,
+    });
+    let start = match heading {
+        Some(start) => start,
+        None => return tokens,
+    };
+    let name = match tokens[start] {
+        Token::Start(ref tag) => tag.name.clone(),
+        _ => return tokens,
+    };
+    let (text, end) = html::text_until(&tokens, start + 1, &name);
+    if collapse_whitespace(&text) != *title {
+        return tokens;
+    }
+    tokens
+        .into_iter()
+        .enumerate()
+        .filter(|token| token.0 < start || token.0 >= end)
+        .map(|token| token.1)
+        .collect()
+}
+
+/// Converts sanitized HTML to plain text with a blank line between blocks.
+fn plain_text(content: &str) -> String {
+    let mut blocks = Vec::new();
+    let mut block = String::new();
+    for token in html::tokenize(content) {
+        match token {
+            Token::Text(text) => block.push_str(&text),
+            Token::Start(ref tag) if tag.name == "br" => block.push(' '),
+            Token::Start(ref tag) if BLOCK_ELEMENTS.contains(&tag.name.as_ref()) => {
+                blocks.push(collapse_whitespace(&block));
+                block.clear();
+            }
+            Token::End(ref name) if BLOCK_ELEMENTS.contains(&name.as_ref()) => {
+                blocks.push(collapse_whitespace(&block));
+                block.clear();
+            }
+            _ => {}
+        }
+    }
+    blocks.push(collapse_whitespace(&block));
+    blocks.retain(|block| !block.is_empty());
+    blocks.join("\n\n")
+}
@@ -2,6 +2,8 @@ use std::io;
 use std::fmt::Debug;
 use std::io::{Read, Write, BufRead};
 use std::fs::OpenOptions;
+use std::env;
+use std::process::Command;
 
 pub fn prompt(prompt: &str, buf: &mut String) -> io::Result<usize> {
     let stdout = io::stdout();
@@ -30,6 +32,32 @@ pub fn overwrite_file(path: &str, text: &str) -> io::Result<usize> {
     file.write(text.as_bytes())
 }
 
+/// Opens a URL or file with the browser named by `$BROWSER`, or else the system's default
+/// handler.
+pub fn open_in_browser(target: &str) -> io::Result<()> {
+    let mut command = match env::var("BROWSER") {
+        Ok(ref browser) if !browser.trim().is_empty() => Command::new(browser.trim()),
+        _ if cfg!(target_os = "macos") => Command::new("open"),
+        _ if cfg!(target_os = "windows") => {
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            command
+        }
+        _ => Command::new("xdg-open"),
+    };
+    let status = command.arg(target).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("Could not open {}", target)))
+    }
+}
+
+/// Collapses runs of whitespace (including newlines) into single spaces.
+pub fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
 #[allow(dead_code)]
 pub fn trace<T: Debug>(label: &str, obj: T) -> T {
     println!("{}: {:?}", label, obj);
@@ -0,0 +1,28 @@
+extern crate open_read_later;
+
+mod common;
+
+use std::fs;
+use common::readlater;
+
+#[test]
+fn it_finds_links_by_their_aliases() {
+    let dir = common::temp_dir("alias_test");
+    let list_file = dir.join("list");
+    fs::write(
+        &list_file,
+        "url: https://example.com/article\ntitle: Article\naliases: https://t.co/abc",
+    ).unwrap();
+    let output = readlater(&list_file, &["show", "https://t.co/abc"]);
+    assert!(output.contains("https://example.com/article"));
+
+    readlater(&list_file, &["tag", "add", "https://t.co/abc", "rust", "async"]);
+    readlater(&list_file, &["tag", "remove", "https://t.co/abc", "async"]);
+    let list = common::read_list(&list_file);
+    assert_eq!(list.get_link("https://example.com/article").unwrap().tags, vec!["rust"]);
+
+    readlater(&list_file, &["delete", "https://t.co/abc"]);
+    let list = common::read_list(&list_file);
+    assert_eq!(list.len(), 0);
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,81 @@
+extern crate open_read_later;
+
+use std::env;
+use std::fs;
+use open_read_later::archive::{self, ContentStore, SnapshotInfo};
+use open_read_later::fetch::{self, Resource};
+
+fn asset(url: &str) -> Option<Resource> {
+    let (content_type, bytes): (&str, &[u8]) = match url {
+        "https://example.com/static/site.css" => {
+            ("text/css", b"body { background: url(../img/bg.png) }")
+        }
+        "https://example.com/img/bg.png" => ("image/png", b"png"),
+        "https://example.com/posts/photo.jpg" => ("image/jpeg; charset=binary", b"jpeg!"),
+        _ => return None,
+    };
+    Some(Resource {
+        url: String::from(url),
+        content_type: String::from(content_type),
+        bytes: bytes.to_vec(),
+    })
+}
+
+#[test]
+fn it_inlines_page_assets() {
+    let document = archive::inline_assets(
+        "<html><head><meta charset=\"iso-8859-1\"><title>A &amp; B</title>\
+         <link rel=\"stylesheet\" href=\"/static/site.css\">\
+         <script src=\"app.js\"></script><script>track(\"<b>\")</script></head>\
+         <body onload=\"init()\"><img data-src=\"photo.jpg\" src=\"placeholder.gif\" srcset=\"x 2x\">\
+         <img src=\"missing.png\"><noscript><p>No JS</p></noscript>\
+         <a href=\"../about?x=1&amp;y=2\">About</a> <a href=\"#top\">Top</a></body></html>",
+        "https://example.com/posts/first",
+        asset,
+    );
+    assert_eq!(
+        document,
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>A &amp; B</title>\
+         <style>body { background: url(\"data:image/png;base64,cG5n\") }</style></head>\
+         <body><img src=\"data:image/jpeg;base64,anBlZyE=\">\
+         <img src=\"https://example.com/posts/missing.png\"><p>No JS</p>\
+         <a href=\"https://example.com/about?x=1&amp;y=2\">About</a> <a href=\"#top\">Top</a>\
+         </body></html>"
+    );
+}
+
+#[test]
+fn it_resolves_relative_urls() {
+    let base = "https://example.com/a/b/page.html?q=1#top";
+    assert_eq!(fetch::resolve(base, "c.png"), "https://example.com/a/b/c.png");
+    assert_eq!(fetch::resolve(base, "../../c/./d.png"), "https://example.com/c/d.png");
+    assert_eq!(fetch::resolve(base, "/e"), "https://example.com/e");
+    assert_eq!(fetch::resolve(base, "//cdn.example.com/f"), "https://cdn.example.com/f");
+    assert_eq!(fetch::resolve(base, "?q=2"), "https://example.com/a/b/page.html?q=2");
+    assert_eq!(fetch::resolve(base, "#other"), "https://example.com/a/b/page.html?q=1#other");
+    assert_eq!(fetch::resolve(base, "data:,x"), "data:,x");
+    assert_eq!(fetch::resolve("https://example.com", "g"), "https://example.com/g");
+}
+
+#[test]
+fn it_stores_snapshots_by_url() {
+    let root = env::temp_dir().join(format!("open_read_later_archive_test_{}", std::process::id()));
+    let store = ContentStore::new(&root);
+    let url = "https://example.com/article";
+    assert_eq!(store.snapshot(url), None);
+
+    let info = SnapshotInfo {
+        url: String::from(url),
+        fetched_url: String::from("https://www.example.com/article"),
+        archived: 1500000000,
+    };
+    let path = store.save_snapshot(&info, "<p>Saved</p>").unwrap();
+    assert_eq!(path, root.join(archive::key(url)).join("snapshot.html"));
+    assert_eq!(store.snapshot(url), Some(String::from("<p>Saved</p>")));
+    assert_eq!(store.snapshot_info(url), Some(info));
+    assert!(archive::key(url) != archive::key("https://example.com/article2"));
+
+    store.delete(url).unwrap();
+    assert_eq!(store.snapshot(url), None);
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,109 @@
+extern crate open_read_later;
+
+use open_read_later::formats::bibtex;
+use open_read_later::read_later_list::{LinkEntry, ReadLaterList};
+
+#[test]
+fn it_round_trips_bibtex() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://doi.org/10.1145/3158154
+title: RustBelt: Securing the Foundations of the Rust Programming Language
+tags: rust, type systems
+added: 1500000000
+---
+url: https://www.example.com/a_b?x=1&y=2
+title: Example {100%} & more",
+    ).unwrap();
+
+    let rendered = bibtex::render(&list);
+    let rustbelt = list.get_link("https://doi.org/10.1145/3158154").unwrap();
+    let key = bibtex::citation_key(rustbelt);
+    assert!(key.starts_with("doi_rustbelt_"));
+    assert_eq!(key, bibtex::citation_key(&rustbelt.clone()));
+    assert!(rendered.starts_with(&format!(
+        "@article{{{},
+  title = {{RustBelt: Securing the Foundations of the Rust Programming Language}},
+  doi = {{10.1145/3158154}},
+  url = {{https://doi.org/10.1145/3158154}},
+  urldate = {{2017-07-14}},
+  keywords = {{rust, type systems}}
+}}
+",
+        key
+    )));
+    assert!(rendered.contains("@misc{example_example_"));
+    assert!(rendered.contains("  title = {Example \\{100\\%\\} \\& more},\n"));
+    assert!(rendered.contains("  url = {https://www.example.com/a_b?x=1&y=2}\n"));
+
+    let mut reimported = ReadLaterList::new();
+    reimported.add_links(bibtex::parse(&rendered).unwrap().links);
+    let mut expected = list.clone();
+    expected.update_link(LinkEntry {
+        added: Some(1499990400),
+        ..rustbelt.clone()
+    });
+    assert_eq!(reimported, expected);
+}
+
+#[test]
+fn it_keeps_citation_keys_unique_and_stable() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://example.com/notes/19
+title: Meeting notes
+---
+url: https://example.com/notes/214
+title: Meeting notes",
+    ).unwrap();
+    let key = |url: &str| bibtex::citation_key(list.get_link(url).unwrap());
+    // The hashes of these URLs only differ after their first four digits
+    assert_eq!(key("https://example.com/notes/19"), "example_meeting_2746a220");
+    assert_eq!(key("https://example.com/notes/214"), "example_meeting_2746eb55");
+
+    // Adding a link doesn't change the keys of the others
+    let rendered = bibtex::render(&list);
+    let mut longer = list.clone();
+    longer.add_links(
+        ReadLaterList::parse("url: https://example.com/notes/3\ntitle: Meeting notes")
+            .unwrap()
+            .iter_links()
+            .cloned()
+            .collect(),
+    );
+    let longer_rendered = bibtex::render(&longer);
+    for entry in rendered.split("\n\n") {
+        assert!(longer_rendered.contains(entry));
+    }
+    assert_eq!(bibtex::parse(&longer_rendered).unwrap().links.len(), 3);
+}
+
+#[test]
+fn it_parses_bibtex_databases() {
+    let database = r#"
+@string{acm = "ACM"}
+@comment{ignored}
+@Article{jung2017,
+  author = {Jung, Ralf and Jourdan, Jacques-Henri},
+  title = "{RustBelt}: Securing the " # {Foundations},
+  journal = acm,
+  doi = {10.1145/3158154},
+  keywords = {rust; semantics},
+}
+@book(klabnik2018, title = {The Rust Programming Language}, year = 2018)
+"#;
+
+    let records = bibtex::parse(database).unwrap();
+    assert_eq!(
+        records.links,
+        vec![
+            LinkEntry::builder()
+                .set_url("https://doi.org/10.1145/3158154")
+                .set_title("RustBelt: Securing the Foundations")
+                .add_tags(&mut vec!["rust", "semantics"])
+                .build()
+                .unwrap(),
+        ]
+    );
+    assert_eq!(records.skipped, vec!["klabnik2018: no url or doi"]);
+}
@@ -0,0 +1,145 @@
+extern crate open_read_later;
+extern crate rusqlite;
+
+use std::env;
+use std::fs;
+use open_read_later::formats::{chromium, firefox};
+use open_read_later::read_later_list::{LinkEntry, ReadLaterList};
+use rusqlite::Connection;
+
+#[test]
+fn it_reads_firefox_places() {
+    let path = env::temp_dir().join("open_read_later-test-places.sqlite");
+    let _ = fs::remove_file(&path);
+    {
+        let connection = Connection::open(&path).unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, title TEXT);
+                 CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER,
+                     parent INTEGER, position INTEGER, title TEXT, dateAdded INTEGER, guid TEXT);
+                 INSERT INTO moz_places VALUES
+                     (1, 'https://example.com/', 'Example Domain'),
+                     (2, 'https://rust-lang.org/', 'Rust'),
+                     (3, 'place:sort=8', NULL);
+                 INSERT INTO moz_bookmarks VALUES
+                     (1, 2, NULL, 0, 0, '', 0, 'root________'),
+                     (2, 2, NULL, 1, 0, 'menu', 0, 'menu________'),
+                     (3, 2, NULL, 1, 1, 'toolbar', 0, 'toolbar_____'),
+                     (4, 2, NULL, 1, 2, 'tags', 0, 'tags________'),
+                     (5, 2, NULL, 3, 0, 'Programming', 0, 'aaaaaaaaaaaa'),
+                     (6, 1, 2, 5, 0, 'The Rust Language', 1500000000000000, 'bbbbbbbbbbbb'),
+                     (7, 1, 1, 2, 0, NULL, 1500000001000000, 'cccccccccccc'),
+                     (8, 2, NULL, 4, 0, 'systems', 0, 'dddddddddddd'),
+                     (9, 1, 2, 8, 0, NULL, 0, 'eeeeeeeeeeee'),
+                     (10, 1, 3, 3, 1, 'Recent', 0, 'ffffffffffff');",
+            )
+            .unwrap();
+    }
+
+    let records = firefox::parse(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(
+        records.links,
+        vec![
+            LinkEntry::builder()
+                .set_url("https://example.com/")
+                .set_title("Example Domain")
+                .set_added(1500000001)
+                .build()
+                .unwrap(),
+            LinkEntry::builder()
+                .set_url("https://rust-lang.org/")
+                .set_title("The Rust Language")
+                .add_tags(&mut vec!["Programming", "systems"])
+                .set_added(1500000000)
+                .build()
+                .unwrap(),
+        ]
+    );
+    assert_eq!(records.skipped, vec!["place:sort=8: not a web page"]);
+}
+
+#[test]
+fn it_stops_at_cycles_of_firefox_folders() {
+    let path = env::temp_dir().join("open_read_later-test-places-cycle.sqlite");
+    let _ = fs::remove_file(&path);
+    {
+        let connection = Connection::open(&path).unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE moz_places (id INTEGER PRIMARY KEY, url TEXT, title TEXT);
+                 CREATE TABLE moz_bookmarks (id INTEGER PRIMARY KEY, type INTEGER, fk INTEGER,
+                     parent INTEGER, position INTEGER, title TEXT, dateAdded INTEGER, guid TEXT);
+                 INSERT INTO moz_places VALUES (1, 'https://example.com/', 'Example Domain');
+                 INSERT INTO moz_bookmarks VALUES
+                     (1, 2, NULL, 2, 0, 'Outer', 0, 'aaaaaaaaaaaa'),
+                     (2, 2, NULL, 1, 0, 'Inner', 0, 'bbbbbbbbbbbb'),
+                     (3, 1, 1, 2, 0, NULL, 0, 'cccccccccccc');",
+            )
+            .unwrap();
+    }
+
+    let records = firefox::parse(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(records.links.len(), 1);
+    assert_eq!(records.links[0].tags, vec!["Outer", "Inner"]);
+}
+
+#[test]
+fn it_reads_chromium_bookmarks_and_dedupes() {
+    let bookmarks = r#"{
+   "checksum": "abc",
+   "roots": {
+      "bookmark_bar": {
+         "children": [ {
+            "children": [ {
+               "date_added": "13144946400000000",
+               "name": "Rust",
+               "type": "url",
+               "url": "https://rust-lang.org/"
+            } ],
+            "name": "Programming",
+            "type": "folder"
+         }, {
+            "date_added": "13144946401000000",
+            "name": "Example",
+            "type": "url",
+            "url": "https://example.com/"
+         } ],
+         "name": "Bookmarks bar",
+         "type": "folder"
+      },
+      "other": { "children": [  ], "name": "Other bookmarks", "type": "folder" }
+   },
+   "version": 1
+}"#;
+
+    let mut records = chromium::parse(bookmarks).unwrap();
+    let list = ReadLaterList::parse(
+        "\
+url: https://rust-lang.org/
+title: My favorite language
+tags: rust
+read: true",
+    ).unwrap();
+    records.merge_into_existing(&list);
+    assert_eq!(
+        records.links,
+        vec![
+            LinkEntry::builder()
+                .set_url("https://rust-lang.org/")
+                .set_title("My favorite language")
+                .add_tags(&mut vec!["rust", "Programming"])
+                .set_read(true)
+                .build()
+                .unwrap(),
+            LinkEntry::builder()
+                .set_url("https://example.com/")
+                .set_title("Example")
+                .set_added(1500472801)
+                .build()
+                .unwrap(),
+        ]
+    );
+}
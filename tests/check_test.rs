@@ -0,0 +1,127 @@
+extern crate open_read_later;
+extern crate serde_json;
+
+mod common;
+
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+use open_read_later::check::{self, CheckOptions, Outcome};
+
+/// Serves a few fixed routes on a local port until the test ends, returning the base URL.
+fn serve() -> String {
+    common::serve(|method, path| {
+        let (status, location) = match (method, path) {
+            (_, "/ok") | (_, "/new") => ("200 OK", None),
+            ("HEAD", "/no-head") => ("405 Method Not Allowed", None),
+            (_, "/no-head") => ("200 OK", None),
+            (_, "/gone") => ("404 Not Found", None),
+            (_, "/moved") => ("301 Moved Permanently", Some("/ok")),
+            (_, "/renamed") => ("301 Moved Permanently", Some("/new")),
+            (_, "/temporary") => ("302 Found", Some("/moved")),
+            (_, "/loop") => ("301 Moved Permanently", Some("/loop")),
+            (_, "/slow") => {
+                thread::sleep(Duration::from_secs(2));
+                ("200 OK", None)
+            }
+            _ => ("500 Internal Server Error", None),
+        };
+        let location = location
+            .map(|location| format!("Location: {}\r\n", location))
+            .unwrap_or_default();
+        common::response(status, &location, "")
+    })
+}
+
+#[test]
+fn it_reports_link_outcomes() {
+    let base = serve();
+    let urls = ["/ok", "/no-head", "/gone", "/moved", "/temporary", "/loop", "/slow"]
+        .iter()
+        .map(|path| format!("{}{}", base, path))
+        .collect::<Vec<String>>();
+    let options = CheckOptions {
+        host_interval: Duration::from_millis(0),
+        timeout: Duration::from_millis(500),
+        ..CheckOptions::default()
+    };
+    let outcomes = check::check_all(&urls, &options)
+        .into_iter()
+        .map(|result| result.outcome)
+        .collect::<Vec<Outcome>>();
+    assert_eq!(
+        outcomes,
+        vec![
+            Outcome::Ok { status: 200 },
+            Outcome::Ok { status: 200 },
+            Outcome::HttpError { status: 404 },
+            Outcome::Redirected {
+                status: 200,
+                to: format!("{}/ok", base),
+                permanent: true,
+            },
+            Outcome::Redirected {
+                status: 200,
+                to: format!("{}/ok", base),
+                permanent: false,
+            },
+            Outcome::TooManyRedirects,
+            Outcome::Timeout,
+        ]
+    );
+    assert!(!outcomes[4].is_broken());
+    assert!(outcomes[6].is_broken());
+}
+
+#[test]
+fn it_spaces_out_requests_to_a_host() {
+    let base = serve();
+    let urls = vec![format!("{}/ok", base); 3];
+    let options = CheckOptions {
+        host_interval: Duration::from_millis(200),
+        ..CheckOptions::default()
+    };
+    let start = Instant::now();
+    let results = check::check_all(&urls, &options);
+    assert!(start.elapsed() >= Duration::from_millis(400));
+    assert!(results.iter().all(|result| result.outcome == Outcome::Ok { status: 200 }));
+}
+
+#[test]
+fn it_tags_broken_links_and_updates_redirects() {
+    let base = serve();
+    let dir = common::temp_dir("check_test");
+    let list_file = dir.join("list");
+    fs::write(
+        &list_file,
+        format!(
+            "url: {0}/ok\ntitle: Fixed\ntags: broken, rust\n---\n\
+             url: {0}/gone\ntitle: Gone\n---\n\
+             url: {0}/renamed\ntitle: Renamed\n---\n\
+             url: {0}/moved\ntitle: Moved",
+            base
+        ),
+    ).unwrap();
+    let output = common::run(
+        &list_file,
+        &["--json", "check", "--tag-broken", "--update-redirects", "--host-delay", "0"],
+    );
+
+    // Only the results go to stdout with --json
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(results.as_array().unwrap().len(), 4);
+    let messages = String::from_utf8(output.stderr).unwrap();
+    assert!(messages.contains(&format!("Updated {0}/renamed to {0}/new", base)));
+    assert!(messages.contains(&format!("Not updating {0}/moved: {0}/ok is already saved", base)));
+
+    let list = common::read_list(&list_file);
+    let link = |path: &str| list.get_link(&format!("{}{}", base, path)).cloned();
+    assert_eq!(link("/ok").unwrap().tags, vec!["rust"]);
+    assert_eq!(link("/gone").unwrap().tags, vec!["broken"]);
+    assert!(link("/renamed").is_none());
+    let updated = link("/new").unwrap();
+    assert_eq!(updated.title, "Renamed");
+    assert_eq!(updated.aliases, vec![format!("{}/renamed", base)]);
+    assert!(link("/moved").is_some());
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,78 @@
+// Each test file uses only some of these helpers
+#![allow(dead_code)]
+
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::Arc;
+use std::thread;
+use open_read_later::read_later_list::ReadLaterList;
+
+/// Runs the CLI on a list file.
+pub fn run(list_file: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_readlater"))
+        .arg("--file")
+        .arg(list_file)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Runs the CLI on a list file, returning what it printed.
+pub fn readlater(list_file: &Path, args: &[&str]) -> String {
+    String::from_utf8(run(list_file, args).stdout).unwrap()
+}
+
+pub fn read_list(list_file: &Path) -> ReadLaterList {
+    ReadLaterList::parse(&fs::read_to_string(list_file).unwrap()).unwrap()
+}
+
+/// Creates a directory for a test's files, named after the test and this process.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("open_read_later_{}_{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Serves HTTP on a local port until the test ends, returning the base URL. `respond` gets
+/// the method and path of each request and returns the whole response.
+pub fn serve<F>(respond: F) -> String
+where
+    F: Fn(&str, &str) -> String + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let respond = Arc::new(respond);
+    thread::spawn(move || for stream in listener.incoming() {
+        let mut stream = stream.unwrap();
+        let respond = respond.clone();
+        thread::spawn(move || {
+            let mut request = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            request.read_line(&mut request_line).unwrap();
+            let mut line = String::from("-");
+            while request.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                line.clear();
+            }
+            let mut parts = request_line.split_whitespace();
+            let method = parts.next().unwrap_or("");
+            let path = parts.next().unwrap_or("");
+            let _ = stream.write_all(respond(method, path).as_bytes());
+        });
+    });
+    format!("http://{}", address)
+}
+
+/// Returns a response with the given status line, extra headers and body.
+pub fn response(status: &str, headers: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        headers,
+        body.len(),
+        body
+    )
+}
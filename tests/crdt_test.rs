@@ -0,0 +1,125 @@
+extern crate open_read_later;
+
+use open_read_later::crdt::{self, ReplicatedList};
+use open_read_later::read_later_list::{LinkEntry, ReadLaterList};
+
+fn link(url: &str, title: &str, tags: Vec<&str>) -> LinkEntry {
+    LinkEntry::builder()
+        .set_url(url)
+        .set_title(title)
+        .add_tags(&mut tags.clone())
+        .build()
+        .unwrap()
+}
+
+/// Merges two replicas in both orders, checking that they converge, and returns the result.
+fn merge_both_ways(a: &ReplicatedList, b: &ReplicatedList) -> ReadLaterList {
+    let mut a_merged = a.clone();
+    a_merged.merge(b);
+    let mut b_merged = b.clone();
+    b_merged.merge(a);
+    assert_eq!(a_merged.to_read_later_list(), b_merged.to_read_later_list());
+    a_merged.to_read_later_list()
+}
+
+#[test]
+fn it_converges_regardless_of_merge_order() {
+    let mut laptop = ReplicatedList::new("laptop");
+    laptop.add_link(link("https://example.com", "Example", vec!["tag1"]));
+    let mut desktop = ReplicatedList::new("desktop");
+    desktop.merge(&laptop);
+
+    laptop.add_link(link("https://example.com", "Example Domain", vec!["tag1", "tag2"]));
+    laptop.add_link(link("https://jeremydormitzer.com", "Jeremy", vec![]));
+    desktop.add_link(link("https://example.com", "Example", vec!["tag3"]));
+    desktop.add_link(link("https://rust-lang.org", "Rust", vec!["rust"]));
+
+    let merged = merge_both_ways(&laptop, &desktop);
+    assert_eq!(merged.len(), 3);
+    let mut example = merged.get_link("https://example.com").unwrap().clone();
+    example.tags.sort();
+    assert_eq!(example.title, "Example Domain");
+    assert_eq!(example.tags, vec!["tag2", "tag3"]);
+}
+
+#[test]
+fn it_breaks_ties_between_concurrent_edits_by_replica() {
+    let mut laptop = ReplicatedList::new("laptop");
+    laptop.add_link(link("https://example.com", "Example", vec![]));
+    let mut desktop = ReplicatedList::new("desktop");
+    desktop.merge(&laptop);
+
+    // Both edits get the same counter, so the stamps differ only by replica ID
+    laptop.add_link(link("https://example.com", "Laptop title", vec![]));
+    desktop.add_link(link("https://example.com", "Desktop title", vec![]));
+
+    let merged = merge_both_ways(&laptop, &desktop);
+    assert_eq!(merged.get_link("https://example.com").unwrap().title, "Laptop title");
+}
+
+#[test]
+fn it_keeps_concurrent_adds_over_removes() {
+    let mut laptop = ReplicatedList::new("laptop");
+    laptop.add_link(link("https://example.com", "Example", vec!["tag1"]));
+    laptop.add_link(link("https://rust-lang.org", "Rust", vec![]));
+    let mut desktop = ReplicatedList::new("desktop");
+    desktop.merge(&laptop);
+
+    // A delete that the other replica doesn't touch wins
+    laptop.delete_link("https://rust-lang.org");
+    // A link deleted on one side but saved again or edited on the other survives
+    laptop.delete_link("https://example.com");
+    desktop.add_link(link("https://example.com", "Example", vec!["tag1", "tag2"]));
+    let merged = merge_both_ways(&laptop, &desktop);
+    assert!(merged.get_link("https://rust-lang.org").is_none());
+    assert_eq!(merged.get_link("https://example.com").unwrap().tags, vec!["tag2"]);
+
+    let mut phone = ReplicatedList::new("phone");
+    phone.merge(&laptop);
+    phone.add_link(link("https://rust-lang.org", "Rust again", vec![]));
+    desktop.delete_link("https://rust-lang.org");
+    let merged = merge_both_ways(&phone, &desktop);
+    assert_eq!(merged.get_link("https://rust-lang.org").unwrap().title, "Rust again");
+}
+
+#[test]
+fn it_detects_replicas_sharing_an_id() {
+    let mut laptop = ReplicatedList::new("laptop");
+    laptop.add_link(link("https://example.com", "Example", vec![]));
+    let mut desktop = ReplicatedList::new("desktop");
+    desktop.merge(&laptop);
+    desktop.add_link(link("https://rust-lang.org", "Rust", vec![]));
+    assert!(!laptop.conflicts_with(&desktop));
+    assert!(!desktop.conflicts_with(&laptop));
+
+    let mut impostor = ReplicatedList::new("laptop");
+    impostor.add_link(link("https://jeremydormitzer.com", "Jeremy", vec![]));
+    assert!(laptop.conflicts_with(&impostor));
+
+    assert!(crdt::random_replica_id() != crdt::random_replica_id());
+}
+
+#[test]
+fn it_records_list_changes() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://example.com
+title: Example
+tags: tag1, tag2
+---
+url: https://jeremydormitzer.com
+title: Jeremy",
+    ).unwrap();
+    let mut replica = ReplicatedList::new("laptop");
+    replica.record(&list);
+    assert_eq!(replica.to_read_later_list(), list);
+
+    let edited = ReadLaterList::parse(
+        "\
+url: https://example.com
+title: Example
+tags: tag2",
+    ).unwrap();
+    replica.record(&edited);
+    assert_eq!(replica.to_read_later_list(), edited);
+}
@@ -0,0 +1,94 @@
+extern crate open_read_later;
+
+use open_read_later::formats::csv::{self, ColumnMapping};
+use open_read_later::read_later_list::{LinkEntry, ReadLaterList};
+
+#[test]
+fn it_round_trips_csv() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://example.com
+title: Example, with \"quotes\"
+tags: tag1, tag2
+added: 1500000000
+---
+url: https://rust-lang.org
+title: Rust
+read: true",
+    ).unwrap();
+
+    let rendered = csv::render(&list, b',', &ColumnMapping::new()).unwrap();
+    assert_eq!(
+        rendered,
+        "\
+url,title,tags,added,read
+https://example.com,\"Example, with \"\"quotes\"\"\",\"tag1, tag2\",1500000000,false
+https://rust-lang.org,Rust,,,true
+"
+    );
+
+    let mut reimported = ReadLaterList::new();
+    reimported.add_links(csv::parse(&rendered, b',', &ColumnMapping::new()).unwrap());
+    assert_eq!(reimported, list);
+}
+
+#[test]
+fn it_maps_tsv_columns() {
+    let tsv = "Name\tLink\tLabels\tNotes\nExample\thttps://example.com\ttag1,tag2\tignored\n";
+    let mapping = ColumnMapping::parse(&["url=Link", "title=Name", "tags=Labels"]).unwrap();
+
+    assert_eq!(
+        csv::parse(tsv, b'\t', &mapping).unwrap(),
+        vec![
+            LinkEntry::builder()
+                .set_url("https://example.com")
+                .set_title("Example")
+                .add_tags(&mut vec!["tag1", "tag2"])
+                .build()
+                .unwrap(),
+        ]
+    );
+    assert!(csv::parse(tsv, b'\t', &ColumnMapping::new()).is_err());
+    assert!(ColumnMapping::parse(&["title"]).is_err());
+}
+
+#[test]
+fn it_round_trips_the_other_link_fields() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://example.com/post
+title: Example
+description: A post, with a comma
+author: Jane Doe
+site_name: Example
+published: 2017-07-14
+canonical_url: https://example.com/canonical
+language: en
+aliases: https://example.com/old, https://example.com/older
+word_count: 450
+reading_minutes: 5",
+    ).unwrap();
+    let mapping = ColumnMapping::parse(&[
+        "url",
+        "title",
+        "description",
+        "author",
+        "site_name",
+        "published",
+        "canonical_url",
+        "language",
+        "aliases",
+        "word_count",
+        "reading_minutes",
+    ]).unwrap();
+
+    let rendered = csv::render(&list, b',', &mapping).unwrap();
+    assert!(rendered.starts_with(
+        "url,title,description,author,site_name,published,canonical_url,language,aliases,\
+         word_count,reading_minutes\n"
+    ));
+
+    let mut reimported = ReadLaterList::new();
+    reimported.add_links(csv::parse(&rendered, b',', &mapping).unwrap());
+    assert_eq!(reimported, list);
+}
@@ -0,0 +1,33 @@
+extern crate open_read_later;
+
+use open_read_later::diff::{self, Line};
+
+#[test]
+fn it_diffs_lines() {
+    assert_eq!(
+        diff::diff_lines("a\nb\nc\nd", "a\nc\nx\nd"),
+        vec![
+            Line::Same("a"),
+            Line::Removed("b"),
+            Line::Same("c"),
+            Line::Added("x"),
+            Line::Same("d"),
+        ]
+    );
+    assert_eq!(diff::diff_lines("", "a"), vec![Line::Added("a")]);
+    assert_eq!(diff::unified_diff("same\ntext", "same\ntext", 3), "");
+}
+
+#[test]
+fn it_renders_unified_diffs() {
+    let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10";
+    let new = "1\n2\nthree\n4\n5\n6\n7\n8\n9\n10\n11";
+    assert_eq!(
+        diff::unified_diff(old, new, 1),
+        "@@ -2,3 +2,3 @@\n 2\n-3\n+three\n 4\n@@ -10,1 +10,2 @@\n 10\n+11\n"
+    );
+    assert_eq!(
+        diff::unified_diff("b\nc", "a\nb\nc", 0),
+        "@@ -0,0 +1,1 @@\n+a\n"
+    );
+}
@@ -0,0 +1,121 @@
+extern crate open_read_later;
+extern crate zip;
+
+mod common;
+
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use open_read_later::formats::epub::{self, Order};
+use open_read_later::read_later_list::ReadLaterList;
+
+fn read_file(book: &[u8], name: &str) -> String {
+    let mut archive = zip::ZipArchive::new(Cursor::new(book)).unwrap();
+    let mut contents = String::new();
+    archive.by_name(name).unwrap().read_to_string(&mut contents).unwrap();
+    contents
+}
+
+#[test]
+fn it_renders_epub_books() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://example.com/new
+title: Newer
+tags: rust
+added: 1500000000
+---
+url: https://example.com/old
+title: Older & wiser
+tags: rust, async
+added: 1400000000
+---
+url: https://example.com/untagged
+title: Untagged link",
+    ).unwrap();
+
+    let book = epub::render(&list, "Reading", Order::Tag, 1500000000, |link| {
+        if link.url == "https://example.com/new" {
+            Some(String::from(
+                "<html><head><title>x</title></head><body><script>bad()</script>\
+                 <article><p>Hello<br>world <img src=\"https://example.com/a.png\"></article>",
+            ))
+        } else {
+            None
+        }
+    }).unwrap();
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(&book[..])).unwrap();
+    assert_eq!(archive.by_index(0).unwrap().name(), "mimetype");
+    assert_eq!(read_file(&book, "mimetype"), "application/epub+zip");
+    assert!(read_file(&book, "OEBPS/content.opf").contains(
+        "<meta property=\"dcterms:modified\">2017-07-14T02:40:00Z</meta>"
+    ));
+
+    let nav = read_file(&book, "OEBPS/nav.xhtml");
+    let async_section = nav.find("<span>async</span>").unwrap();
+    let rust_section = nav.find("<span>rust</span>").unwrap();
+    let untagged_section = nav.find("<span>Untagged</span>").unwrap();
+    assert!(async_section < rust_section && rust_section < untagged_section);
+    assert!(nav.contains("<li><a href=\"chapters/0001.xhtml\">Older &amp; wiser</a></li>"));
+    assert!(nav.contains("<li><a href=\"chapters/0002.xhtml\">Newer</a></li>"));
+
+    let archived = read_file(&book, "OEBPS/chapters/0002.xhtml");
+    assert!(archived.contains("    <hr/>\n<p>Hello<br/>world </p>\n"));
+    let fallback = read_file(&book, "OEBPS/chapters/0003.xhtml");
+    assert!(fallback.contains(
+        "    <h1>Untagged link</h1>\n    <p><a href=\"https://example.com/untagged\">\
+         https://example.com/untagged</a></p>\n  </body>"
+    ));
+}
+
+#[test]
+fn it_orders_epub_chapters_by_date() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://example.com/undated
+title: Undated
+---
+url: https://example.com/new
+title: Newer
+added: 1500000000
+---
+url: https://example.com/old
+title: Older
+added: 1400000000",
+    ).unwrap();
+    let book = epub::render(&list, "Reading", Order::Date, 0, |_| None).unwrap();
+    let ncx = read_file(&book, "OEBPS/toc.ncx");
+    let titles = ["Older", "Newer", "Undated"]
+        .iter()
+        .map(|title| ncx.find(&format!("<text>{}</text>", title)).unwrap())
+        .collect::<Vec<usize>>();
+    assert!(titles[0] < titles[1] && titles[1] < titles[2]);
+}
+
+#[test]
+fn it_refuses_to_render_empty_books() {
+    let list = ReadLaterList::new();
+    assert!(epub::render(&list, "Reading", Order::Date, 0, |_| None).is_err());
+}
+
+#[test]
+fn it_exports_epub_books_with_their_own_title() {
+    let dir = common::temp_dir("epub_test");
+    let list_file = dir.join("list");
+    let book = dir.join("book.epub");
+    let book = book.to_str().unwrap();
+    fs::write(&list_file, "").unwrap();
+    let output = common::readlater(&list_file, &["export", "--format", "epub", "--out", book]);
+    assert!(output.contains("There are no links to put in the book"));
+    assert!(!Path::new(book).exists());
+
+    fs::write(&list_file, "url: https://example.com\ntitle: Example").unwrap();
+    common::readlater(
+        &list_file,
+        &["export", "--format", "epub", "--out", book, "--book-title", "Weekend reading"],
+    );
+    let package = read_file(&fs::read(book).unwrap(), "OEBPS/content.opf");
+    assert!(package.contains("<dc:title>Weekend reading</dc:title>"));
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,58 @@
+extern crate open_read_later;
+
+use open_read_later::formats::feed;
+use open_read_later::read_later_list::ReadLaterList;
+
+fn list() -> ReadLaterList {
+    ReadLaterList::parse(
+        "\
+url: https://example.com/?a=1&b=2
+title: Example <3
+tags: tag1, tag2
+added: 1500000000
+---
+url: https://rust-lang.org
+title: Rust
+added: 1600000000
+---
+url: https://jeremydormitzer.com
+title: Jeremy",
+    ).unwrap()
+}
+
+#[test]
+fn it_renders_atom_feeds() {
+    let rendered = feed::render_atom(&list(), "Reading", "https://example.com/feed", 1700000000);
+    assert!(rendered.contains("<updated>2023-11-14T22:13:20+00:00</updated>"));
+    assert!(rendered.contains(
+        "  <entry>
+    <title>Example &lt;3</title>
+    <id>https://example.com/?a=1&amp;b=2</id>
+    <link href=\"https://example.com/?a=1&amp;b=2\"/>
+    <updated>2017-07-14T02:40:00+00:00</updated>
+    <category term=\"tag1\"/>
+    <category term=\"tag2\"/>
+  </entry>"
+    ));
+    let rust = rendered.find("<title>Rust</title>").unwrap();
+    let example = rendered.find("<title>Example &lt;3</title>").unwrap();
+    assert!(rust < example);
+}
+
+#[test]
+fn it_renders_rss_feeds() {
+    let results = list().search("example");
+    let rendered = feed::render_rss(&results, "Reading", "https://example.com/feed", 1700000000);
+    assert!(rendered.contains("<lastBuildDate>Tue, 14 Nov 2023 22:13:20 +0000</lastBuildDate>"));
+    assert!(rendered.contains(
+        "    <item>
+      <title>Example &lt;3</title>
+      <link>https://example.com/?a=1&amp;b=2</link>
+      <guid isPermaLink=\"true\">https://example.com/?a=1&amp;b=2</guid>
+      <pubDate>Fri, 14 Jul 2017 02:40:00 +0000</pubDate>
+      <category>tag1</category>
+      <category>tag2</category>
+    </item>"
+    ));
+    assert!(!rendered.contains("rust-lang.org"));
+}
@@ -0,0 +1,111 @@
+extern crate open_read_later;
+
+mod common;
+
+use std::time::Duration;
+use open_read_later::fetch;
+
+/// Serves redirects on a local port until the test ends, returning the base URL. Each route
+/// maps a path to the location it redirects to; other paths get an empty page.
+fn serve_redirects(routes: &'static [(&'static str, &'static str)]) -> String {
+    common::serve(move |_, path| match routes.iter().find(|route| route.0 == path) {
+        Some(route) => {
+            common::response(
+                "301 Moved Permanently",
+                &format!("Location: {}\r\n", route.1),
+                "",
+            )
+        }
+        None => common::response("200 OK", "", ""),
+    })
+}
+
+#[test]
+fn it_extracts_titles() {
+    assert_eq!(
+        fetch::extract_title("<html><head><title>\n  Rust &amp; you </title>"),
+        Some(String::from("Rust & you"))
+    );
+    assert_eq!(
+        fetch::extract_title(
+            "<head><title> </title><meta property=\"og:title\" content=\"From OpenGraph\"></head>\
+             <body><h1>Heading</h1></body>"
+        ),
+        Some(String::from("From OpenGraph"))
+    );
+    assert_eq!(
+        fetch::extract_title("<body><h1>A <em>nested</em> heading</h1><h1>Second</h1></body>"),
+        Some(String::from("A nested heading"))
+    );
+    assert_eq!(fetch::extract_title("<p>No title here</p>"), None);
+}
+
+#[test]
+fn it_expands_redirecting_urls() {
+    let base = serve_redirects(&[
+        ("/short", "/hop"),
+        ("/hop", "final?from=short"),
+        ("/loop", "/loop-back"),
+        ("/loop-back", "/loop"),
+    ]);
+    let timeout = Duration::from_secs(5);
+    assert_eq!(
+        fetch::expand_url(&format!("{}/short", base), timeout),
+        Ok(format!("{}/final?from=short", base))
+    );
+    assert_eq!(
+        fetch::expand_url(&format!("{}/article", base), timeout),
+        Ok(format!("{}/article", base))
+    );
+    assert_eq!(
+        fetch::expand_url(&format!("{}/loop", base), timeout),
+        Err(format!("{}/loop redirects in a loop", base))
+    );
+}
+
+#[test]
+fn it_extracts_page_metadata() {
+    let metadata = fetch::extract_metadata(
+        r#"<html lang="en-GB"><head>
+<title>Article | Example News</title>
+<meta name="description" content="Plain description">
+<meta property="og:description" content="OpenGraph
+  description">
+<meta property="og:site_name" content="Example News">
+<meta property="article:author" content="https://example.com/people/jane">
+<meta name="twitter:creator" content="@jane">
+<link rel="canonical" href="/articles/1">
+<script type="application/ld+json">
+{"@context": "https://schema.org", "@graph": [
+  {"@type": "NewsArticle", "datePublished": "2017-07-14T02:40:00Z",
+   "author": [{"@type": "Person", "name": "Jane Doe"}, {"@type": "Person", "name": "John Roe"}]}
+]}
+</script>
+</head></html>"#,
+        "https://example.com/articles/1?ref=feed",
+    );
+    assert_eq!(
+        metadata,
+        fetch::Metadata {
+            title: Some(String::from("Article | Example News")),
+            description: Some(String::from("OpenGraph description")),
+            author: Some(String::from("Jane Doe, John Roe")),
+            site_name: Some(String::from("Example News")),
+            published: Some(String::from("2017-07-14T02:40:00Z")),
+            canonical_url: Some(String::from("https://example.com/articles/1")),
+            language: Some(String::from("en-GB")),
+        }
+    );
+
+    let metadata = fetch::extract_metadata(
+        "<meta name=\"twitter:description\" content=\"From Twitter\">\
+         <meta name=\"author\" content=\"Jane Doe\">\
+         <meta property=\"og:locale\" content=\"fr_FR\">\
+         <meta property=\"og:url\" content=\"other.html\">",
+        "https://example.com/blog/post.html",
+    );
+    assert_eq!(metadata.description, Some(String::from("From Twitter")));
+    assert_eq!(metadata.author, Some(String::from("Jane Doe")));
+    assert_eq!(metadata.language, Some(String::from("fr-FR")));
+    assert_eq!(metadata.canonical_url, Some(String::from("https://example.com/blog/other.html")));
+}
@@ -0,0 +1,98 @@
+extern crate open_read_later;
+
+use std::env;
+use std::fs;
+use open_read_later::archive::{ContentStore, SnapshotInfo};
+use open_read_later::index::{self, SearchIndex};
+use open_read_later::readability::Article;
+
+fn urls(index: &SearchIndex, query: &str) -> Vec<String> {
+    index.search(query).into_iter().map(|result| result.url).collect()
+}
+
+#[test]
+fn it_ranks_matches_with_bm25() {
+    let mut index = SearchIndex::new();
+    index.add("https://example.com/borrowing", "Borrowing in Rust: the borrow checker checks borrows.");
+    index.add(
+        "https://example.com/ownership",
+        "Ownership is how Rust manages memory. The borrow checker enforces ownership rules \
+         and lifetimes, across a long and winding article about many other things entirely.",
+    );
+    index.add("https://example.com/python", "Python manages memory with reference counting.");
+
+    assert_eq!(
+        urls(&index, "BORROW checker"),
+        vec!["https://example.com/borrowing", "https://example.com/ownership"]
+    );
+    assert_eq!(
+        urls(&index, "memory"),
+        vec!["https://example.com/python", "https://example.com/ownership"]
+    );
+    assert!(index.search("javascript").is_empty());
+    let results = index.search("ownership memory");
+    assert_eq!(results[0].url, "https://example.com/ownership");
+    assert!(results[0].score > results[1].score);
+
+    index.rename("https://example.com/python", "https://example.org/python");
+    assert_eq!(urls(&index, "counting"), vec!["https://example.org/python"]);
+    index.add("https://example.org/python", "Rewritten without the old words");
+    assert!(index.search("counting").is_empty());
+    index.remove("https://example.com/borrowing");
+    assert_eq!(urls(&index, "borrow"), vec!["https://example.com/ownership"]);
+    assert_eq!(index.len(), 2);
+}
+
+#[test]
+fn it_highlights_the_best_passage() {
+    let text = "One two three four five six. Seven eight, Rust nine ten rust. Eleven twelve.";
+    let highlight = |word: &str| format!("[{}]", word);
+    assert_eq!(
+        index::snippet(text, "rust", 5, highlight),
+        "…eight, [Rust] nine ten [rust].…"
+    );
+    assert_eq!(
+        index::snippet(text, "missing", 3, highlight),
+        "One two three…"
+    );
+    assert_eq!(index::snippet("Short (rust)", "rust", 30, highlight), "Short ([rust])");
+}
+
+#[test]
+fn it_keeps_the_content_store_index_up_to_date() {
+    let root = env::temp_dir().join(format!("open_read_later_index_test_{}", std::process::id()));
+    let store = ContentStore::new(&root);
+    let article = |text: &str| {
+        Article {
+            title: None,
+            byline: None,
+            content: String::new(),
+            text: String::from(text),
+            word_count: 0,
+        }
+    };
+    for &(url, text) in &[
+        ("https://example.com/a", "Archived article about gardening"),
+        ("https://example.com/b", "Another article about cooking"),
+    ] {
+        let info = SnapshotInfo {
+            url: String::from(url),
+            fetched_url: String::from(url),
+            archived: 1500000000,
+        };
+        store.save_snapshot(&info, "<p></p>").unwrap();
+        store.save_article(url, &article(text)).unwrap();
+    }
+    let index = store.search_index().unwrap();
+    assert_eq!(urls(&index, "article"), vec!["https://example.com/a", "https://example.com/b"]);
+
+    store.delete("https://example.com/a").unwrap();
+    store.rename("https://example.com/b", "https://example.org/b").unwrap();
+    let index = store.search_index().unwrap();
+    assert_eq!(urls(&index, "article"), vec!["https://example.org/b"]);
+
+    // A missing index is rebuilt from the stored articles
+    fs::remove_file(root.join("index.json")).unwrap();
+    assert_eq!(store.search_index().unwrap(), index);
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,61 @@
+extern crate open_read_later;
+
+use open_read_later::formats::{instapaper, ImportSummary};
+use open_read_later::read_later_list::{LinkEntry, ReadLaterList};
+
+#[test]
+fn it_parses_instapaper_exports() {
+    let export = "\
+URL,Title,Selection,Folder,Timestamp
+https://example.com,\"Example, Inc.\",,Unread,1500000000
+https://rust-lang.org,Rust,,Archive,1500000001
+https://jeremydormitzer.com,Jeremy,Some highlight,Starred,1500000002
+https://blog.rust-lang.org,Rust Blog,,Rust,1500000003
+,No URL,,Unread,1500000004
+https://example.com,Example again,,Reading,1500000005
+";
+
+    let records = instapaper::parse(export).unwrap();
+    assert_eq!(
+        records.links,
+        vec![
+            LinkEntry::builder()
+                .set_url("https://example.com")
+                .set_title("Example, Inc.")
+                .add_tags(&mut vec!["Reading"])
+                .set_added(1500000000)
+                .build()
+                .unwrap(),
+            LinkEntry::builder()
+                .set_url("https://rust-lang.org")
+                .set_title("Rust")
+                .set_added(1500000001)
+                .set_read(true)
+                .build()
+                .unwrap(),
+            LinkEntry::builder()
+                .set_url("https://jeremydormitzer.com")
+                .set_title("Jeremy")
+                .add_tags(&mut vec!["starred"])
+                .set_added(1500000002)
+                .build()
+                .unwrap(),
+            LinkEntry::builder()
+                .set_url("https://blog.rust-lang.org")
+                .set_title("Rust Blog")
+                .add_tags(&mut vec!["Rust"])
+                .set_added(1500000003)
+                .build()
+                .unwrap(),
+        ]
+    );
+    assert_eq!(records.skipped, vec!["line 6: no URL"]);
+    assert_eq!(records.merged, vec!["https://example.com"]);
+
+    let list = ReadLaterList::parse("url: https://rust-lang.org\ntitle: Rust").unwrap();
+    let summary = ImportSummary::from_records(&list, &records);
+    assert_eq!(
+        summary.to_string(),
+        "3 new, 1 updated, 0 unchanged, 1 merged, 1 skipped"
+    );
+}
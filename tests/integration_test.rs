@@ -1,6 +1,10 @@
 extern crate open_read_later;
+extern crate serde_json;
 
-use open_read_later::read_later_list::{LinkEntry, ReadLaterList};
+mod common;
+
+use std::fs;
+use open_read_later::read_later_list::{self, LinkEntry, ReadLaterList};
 
 #[test]
 fn it_parses_read_later_list() {
@@ -165,3 +169,154 @@ title: Jeremy";
     assert_eq!(read_later_list, read_later_list_constructed);
     assert_eq!(read_later_list.to_string(), read_later_text_without_tags);
 }
+
+#[test]
+fn it_round_trips_added_dates() {
+    let read_later_text = "\
+url: https://example.com
+title: Example
+tags: tag1
+added: 1500000000";
+
+    let mut read_later_list = ReadLaterList::parse(read_later_text).unwrap();
+    read_later_list
+        .add_tags("https://example.com", vec![String::from("tag2")])
+        .unwrap();
+
+    assert_eq!(
+        read_later_list.get_link("https://example.com").unwrap().added,
+        Some(1500000000)
+    );
+    assert_eq!(
+        read_later_list.to_string(),
+        "\
+url: https://example.com
+title: Example
+tags: tag1, tag2
+added: 1500000000"
+    );
+}
+
+#[test]
+fn it_round_trips_page_metadata() {
+    let read_later_text = "\
+url: https://example.com
+title: Example
+read: true
+description: An example page
+author: Jane Doe
+site_name: Example
+published: 2017-07-14
+canonical_url: https://example.com/canonical
+language: en";
+
+    let read_later_list = ReadLaterList::parse(read_later_text).unwrap();
+    let link = read_later_list.get_link("https://example.com").unwrap();
+    assert_eq!(link.author, Some(String::from("Jane Doe")));
+    assert_eq!(link.metadata().len(), 6);
+    assert_eq!(read_later_list.to_string(), read_later_text);
+
+    let link = LinkEntry::builder()
+        .set_url("https://example.com")
+        .set_title("Example")
+        .set_description("  Spans\n  two lines ")
+        .set_author(" ")
+        .build()
+        .unwrap();
+    assert_eq!(link.description, Some(String::from("Spans two lines")));
+    assert_eq!(link.author, None);
+}
+
+#[test]
+fn it_finds_links_by_alias() {
+    let read_later_text = "\
+url: https://example.com/article
+title: Example
+aliases: https://t.co/abc, https://bit.ly/xyz";
+
+    let read_later_list = ReadLaterList::parse(read_later_text).unwrap();
+    assert_eq!(read_later_list.to_string(), read_later_text);
+    assert_eq!(
+        read_later_list.find_link("https://bit.ly/xyz").map(|link| link.url.as_ref()),
+        Some("https://example.com/article")
+    );
+    assert_eq!(
+        read_later_list.find_link("https://example.com/article").map(|link| link.url.as_ref()),
+        Some("https://example.com/article")
+    );
+    assert!(read_later_list.find_link("https://t.co/other").is_none());
+    assert!(read_later_list.get_link("https://t.co/abc").is_none());
+}
+
+#[test]
+fn it_estimates_reading_time() {
+    assert_eq!(read_later_list::reading_minutes(0), 0);
+    assert_eq!(read_later_list::reading_minutes(1), 1);
+    assert_eq!(read_later_list::reading_minutes(2000), 10);
+    assert_eq!(read_later_list::reading_minutes(2001), 11);
+
+    let link = LinkEntry::builder()
+        .set_url("https://example.com")
+        .set_title("Example")
+        .set_word_count(950)
+        .build()
+        .unwrap();
+    assert_eq!(link.reading_minutes, Some(5));
+
+    let read_later_text = "\
+url: https://example.com
+title: Example
+word_count: 950
+reading_minutes: 7";
+    let read_later_list = ReadLaterList::parse(read_later_text).unwrap();
+    let link = read_later_list.get_link("https://example.com").unwrap();
+    assert_eq!(link.word_count, Some(950));
+    assert_eq!(link.reading_minutes, Some(7));
+    assert_eq!(read_later_list.to_string(), read_later_text);
+
+    // An explicit reading time is kept whichever order the fields are in
+    let read_later_list = ReadLaterList::parse(
+        "url: https://example.com\ntitle: Example\nreading_minutes: 7\nword_count: 950",
+    ).unwrap();
+    let link = read_later_list.get_link("https://example.com").unwrap();
+    assert_eq!(link.reading_minutes, Some(7));
+}
+
+#[test]
+fn it_lists_links_that_fit_a_reading_time() {
+    let dir = common::temp_dir("list_test");
+    let list_file = dir.join("list");
+    fs::write(
+        &list_file,
+        "\
+url: https://example.com/short
+title: Short
+reading_minutes: 3
+---
+url: https://example.com/exact
+title: Exact
+reading_minutes: 10
+---
+url: https://example.com/long
+title: Long
+reading_minutes: 25
+---
+url: https://example.com/unknown
+title: Unknown",
+    ).unwrap();
+    let output = common::readlater(&list_file, &["--json", "list", "--max-minutes", "10"]);
+    let listed: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let mut urls = listed["links"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(|url| url.as_str())
+        .collect::<Vec<&str>>();
+    urls.sort();
+    assert_eq!(urls, vec!["https://example.com/exact", "https://example.com/short"]);
+
+    let output = common::run(&list_file, &["list", "--max-minutes", "ten"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("ten is not a whole number"));
+    fs::remove_dir_all(dir).unwrap();
+}
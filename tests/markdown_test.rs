@@ -0,0 +1,67 @@
+extern crate open_read_later;
+
+use open_read_later::formats::markdown;
+use open_read_later::read_later_list::{LinkEntry, ReadLaterList};
+
+#[test]
+fn it_round_trips_markdown() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://en.wikipedia.org/wiki/Rust_(programming_language)
+title: Rust [Wikipedia]
+tags: rust, The coolest
+---
+url: https://jeremydormitzer.com
+title: Jeremy",
+    ).unwrap();
+
+    let rendered = markdown::render(&list, false);
+    assert_eq!(
+        rendered,
+        "\
+- [Rust \\[Wikipedia\\]](<https://en.wikipedia.org/wiki/Rust_(programming_language)>) #rust #The-coolest
+- [Jeremy](https://jeremydormitzer.com)
+"
+    );
+    let reimported = markdown::parse(&rendered).unwrap();
+    assert_eq!(reimported[0].title, "Rust [Wikipedia]");
+    assert_eq!(reimported[0].tags, vec!["rust", "The-coolest"]);
+    assert_eq!(reimported[1], *list.get_link("https://jeremydormitzer.com").unwrap());
+
+    let grouped = markdown::render(&list, true);
+    assert!(grouped.starts_with("## The coolest\n\n- [Rust"));
+    assert!(grouped.ends_with("## Untagged\n\n- [Jeremy](https://jeremydormitzer.com)\n"));
+    assert_eq!(markdown::parse(&grouped).unwrap(), reimported);
+}
+
+#[test]
+fn it_parses_links_from_markdown_documents() {
+    let document = "\
+# Notes
+
+See [the Rust book](https://doc.rust-lang.org/book/ \"The Book\") and <https://example.com>, \
+but not ![a logo](https://example.com/logo.png) or [a section](#notes). #not-a-tag
+
+```
+[in a code block](https://example.org)
+```
+
+* [Example](https://example.com) #tag1";
+
+    assert_eq!(
+        markdown::parse(document).unwrap(),
+        vec![
+            LinkEntry::builder()
+                .set_url("https://doc.rust-lang.org/book/")
+                .set_title("the Rust book")
+                .build()
+                .unwrap(),
+            LinkEntry::builder()
+                .set_url("https://example.com")
+                .set_title("Example")
+                .add_tags(&mut vec!["tag1"])
+                .build()
+                .unwrap(),
+        ]
+    );
+}
@@ -0,0 +1,135 @@
+extern crate open_read_later;
+
+mod common;
+
+use std::fs;
+use open_read_later::formats::{netscape, ImportSummary};
+use open_read_later::read_later_list::{LinkEntry, ReadLaterList};
+
+const BOOKMARKS: &str = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<!-- This is an automatically generated file. -->
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<H1>Bookmarks</H1>
+<DL><p>
+    <DT><H3 ADD_DATE="1500000000" PERSONAL_TOOLBAR_FOLDER="true">Bookmarks bar</H3>
+    <DL><p>
+        <DT><A HREF="https://example.com" ADD_DATE="1500000001" TAGS="tag1,tag2">Example &amp; co</A>
+        <DT><H3>Rust</H3>
+        <DL><p>
+            <DT><A HREF="https://rust-lang.org" ADD_DATE="1500000002">Rust</A>
+            <DD>A systems programming language
+        </DL><p>
+    </DL><p>
+    <DT><A HREF="place:sort=8&maxResults=10">Recently bookmarked</A>
+    <DT><A HREF="https://jeremydormitzer.com"></A>
+</DL><p>
+"#;
+
+#[test]
+fn it_parses_netscape_bookmarks() {
+    let links = netscape::parse(BOOKMARKS).unwrap();
+    assert_eq!(
+        links,
+        vec![
+            LinkEntry::builder()
+                .set_url("https://example.com")
+                .set_title("Example & co")
+                .add_tags(&mut vec!["tag1", "tag2"])
+                .set_added(1500000001)
+                .build()
+                .unwrap(),
+            LinkEntry::builder()
+                .set_url("https://rust-lang.org")
+                .set_title("Rust")
+                .add_tags(&mut vec!["Rust"])
+                .set_added(1500000002)
+                .build()
+                .unwrap(),
+            LinkEntry::builder()
+                .set_url("https://jeremydormitzer.com")
+                .set_title("https://jeremydormitzer.com")
+                .build()
+                .unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn it_summarizes_imports() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://example.com
+title: Example
+---
+url: https://jeremydormitzer.com
+title: https://jeremydormitzer.com",
+    ).unwrap();
+    let links = netscape::parse(BOOKMARKS).unwrap();
+    let summary = ImportSummary::new(&list, &links);
+    assert_eq!(summary.new, vec!["https://rust-lang.org"]);
+    assert_eq!(summary.updated, vec!["https://example.com"]);
+    assert_eq!(summary.unchanged, vec!["https://jeremydormitzer.com"]);
+}
+
+#[test]
+fn it_renders_netscape_bookmarks() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://example.com
+title: Example <3
+tags: tag1, tag2
+added: 1500000001
+---
+url: https://rust-lang.org
+title: Rust
+tags: rust",
+    ).unwrap();
+    let rendered = netscape::render(&list, &[String::from("rust")]);
+    assert!(rendered.starts_with("<!DOCTYPE NETSCAPE-Bookmark-file-1>"));
+    assert!(rendered.contains(
+        "    <DT><H3>rust</H3>
+    <DL><p>
+        <DT><A HREF=\"https://rust-lang.org\" TAGS=\"rust\">Rust</A>
+    </DL><p>
+    <DT><A HREF=\"https://example.com\" ADD_DATE=\"1500000001\" TAGS=\"tag1,tag2\">Example &lt;3</A>
+</DL><p>
+"
+    ));
+
+    let mut reimported = ReadLaterList::new();
+    reimported.add_links(netscape::parse(&rendered).unwrap());
+    assert_eq!(reimported, list);
+}
+
+#[test]
+fn it_keeps_saved_fields_when_importing_an_export() {
+    let dir = common::temp_dir("netscape_test");
+    let list_file = dir.join("list");
+    let export_file = dir.join("bookmarks.html");
+    let export_file = export_file.to_str().unwrap();
+    fs::write(
+        &list_file,
+        "\
+url: https://example.com/article
+title: Article
+tags: rust
+added: 1500000000
+read: true
+aliases: https://t.co/abc
+word_count: 2300
+reading_minutes: 10
+description: A long read
+---
+url: https://example.com/other
+title: Other",
+    ).unwrap();
+    let saved = common::read_list(&list_file);
+    common::readlater(&list_file, &["export", "--format", "netscape", "--out", export_file]);
+    common::readlater(&list_file, &["delete", "https://example.com/other"]);
+
+    let output = common::readlater(&list_file, &["import", "--format", "netscape", export_file]);
+    assert!(output.contains("1 new, 0 updated, 1 unchanged"));
+    assert_eq!(common::read_list(&list_file), saved);
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,72 @@
+extern crate open_read_later;
+
+use open_read_later::formats::opml;
+use open_read_later::read_later_list::{LinkEntry, ReadLaterList};
+
+#[test]
+fn it_parses_opml_outlines() {
+    let outline = r#"<?xml version="1.0" encoding="UTF-8"?>
+<opml version="1.0">
+  <head><title>Subscriptions</title></head>
+  <body>
+    <outline text="Rust" title="Rust">
+      <outline text="This Week in Rust" type="rss" xmlUrl="https://this-week-in-rust.org/rss.xml" htmlUrl="https://this-week-in-rust.org"/>
+      <outline text="Async">
+        <outline text="Tokio" type="rss" xmlUrl="https://tokio.rs/feed.xml"/>
+      </outline>
+    </outline>
+    <outline text="Blogs">
+      <outline text="This Week in Rust" htmlUrl="https://this-week-in-rust.org"/>
+    </outline>
+    <outline text="Example" type="link" url="https://example.com"/>
+  </body>
+</opml>"#;
+
+    assert_eq!(
+        opml::parse(outline).unwrap(),
+        vec![
+            LinkEntry::builder()
+                .set_url("https://this-week-in-rust.org")
+                .set_title("This Week in Rust")
+                .add_tags(&mut vec!["Rust", "Blogs"])
+                .build()
+                .unwrap(),
+            LinkEntry::builder()
+                .set_url("https://tokio.rs/feed.xml")
+                .set_title("Tokio")
+                .add_tags(&mut vec!["Rust", "Async"])
+                .build()
+                .unwrap(),
+            LinkEntry::builder()
+                .set_url("https://example.com")
+                .set_title("Example")
+                .build()
+                .unwrap(),
+        ]
+    );
+    assert!(opml::parse("<html></html>").is_err());
+}
+
+#[test]
+fn it_round_trips_opml() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://example.com
+title: Example & co
+tags: tag1, tag2
+---
+url: https://jeremydormitzer.com
+title: Jeremy",
+    ).unwrap();
+
+    let rendered = opml::render(&list);
+    assert!(rendered.contains(
+        "    <outline text=\"tag1\">
+      <outline text=\"Example &amp; co\" type=\"link\" url=\"https://example.com\" htmlUrl=\"https://example.com\"/>
+    </outline>"
+    ));
+
+    let mut reimported = ReadLaterList::new();
+    reimported.add_links(opml::parse(&rendered).unwrap());
+    assert_eq!(reimported, list);
+}
@@ -0,0 +1,74 @@
+extern crate open_read_later;
+
+use open_read_later::formats::org;
+use open_read_later::read_later_list::ReadLaterList;
+
+#[test]
+fn it_round_trips_org() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://example.com/a
+title: Async [Rust] explained
+tags: rust, async
+added: 1500000000
+---
+url: https://example.com/b
+title: Another link
+read: true",
+    ).unwrap();
+
+    let rendered = org::render(&list);
+    assert_eq!(
+        rendered,
+        "\
+* TODO [[https://example.com/a][Async {Rust} explained]] :rust:async:
+  :PROPERTIES:
+  :ADDED: [2017-07-14 Fri 02:40]
+  :END:
+* DONE [[https://example.com/b][Another link]]
+"
+    );
+
+    let links = org::parse(&rendered).unwrap();
+    assert_eq!(links.len(), 2);
+    assert_eq!(links[0].title, "Async {Rust} explained");
+    assert_eq!(links[0].tags, vec!["rust", "async"]);
+    assert_eq!(links[0].added, Some(1500000000));
+    assert!(!links[0].read);
+    assert!(links[1].read);
+}
+
+#[test]
+fn it_parses_nested_org_headlines() {
+    let links = org::parse(
+        "\
+#+TITLE: Reading
+* Programming :code:
+** [[https://example.com/c]] Notes on parsing :parsers:
+Some body text with https://example.com/ignored
+** A heading without a link
+*** Read https://example.com/d later
+* [#A] [[https://example.com/e][Top level]]
+",
+    ).unwrap();
+    let summary = links
+        .iter()
+        .map(|link| (link.url.as_ref(), link.title.as_ref(), link.tags.join(",")))
+        .collect::<Vec<(&str, &str, String)>>();
+    assert_eq!(
+        summary,
+        vec![
+            ("https://example.com/c", "Notes on parsing", String::from("code,parsers")),
+            ("https://example.com/d", "Read later", String::from("code")),
+            ("https://example.com/e", "Top level", String::new()),
+        ]
+    );
+}
+
+#[test]
+fn it_sanitizes_org_tags() {
+    let list = ReadLaterList::parse(
+        "url: https://example.com\ntitle: Example\ntags: machine learning, c++",
+    ).unwrap();
+    assert!(org::render(&list).contains(" :machine_learning:c__:\n"));
+}
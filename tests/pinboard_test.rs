@@ -0,0 +1,36 @@
+extern crate open_read_later;
+
+use open_read_later::formats::pinboard;
+use open_read_later::read_later_list::LinkEntry;
+
+#[test]
+fn it_parses_pinboard_exports() {
+    let export = r#"[
+  {"href":"https://example.com","description":"Example","extended":"","meta":"abc","hash":"def","time":"2017-07-14T02:40:00Z","shared":"no","toread":"yes","tags":"tag1 tag2"},
+  {"href":"https://rust-lang.org","description":"","extended":"","time":"2017-07-14T02:40:01Z","shared":"yes","toread":"no","tags":""},
+  {"description":"Missing href","tags":""}
+]"#;
+
+    let records = pinboard::parse(export).unwrap();
+    assert_eq!(
+        records.links,
+        vec![
+            LinkEntry::builder()
+                .set_url("https://example.com")
+                .set_title("Example")
+                .add_tags(&mut vec!["tag1", "tag2"])
+                .set_added(1500000000)
+                .build()
+                .unwrap(),
+            LinkEntry::builder()
+                .set_url("https://rust-lang.org")
+                .set_title("https://rust-lang.org")
+                .set_added(1500000001)
+                .set_read(true)
+                .build()
+                .unwrap(),
+        ]
+    );
+    assert_eq!(records.skipped, vec!["bookmark 3: no href"]);
+    assert!(pinboard::parse("{}").is_err());
+}
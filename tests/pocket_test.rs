@@ -0,0 +1,64 @@
+extern crate open_read_later;
+
+use open_read_later::formats::pocket;
+use open_read_later::read_later_list::{LinkEntry, ReadLaterList};
+
+#[test]
+fn it_parses_pocket_exports() {
+    let export = r#"<!DOCTYPE html>
+<html>
+	<head>
+		<meta http-equiv="Content-Type" content="text/html; charset=UTF-8" />
+		<title>Pocket Export</title>
+	</head>
+	<body>
+		<h1>Unread</h1>
+		<ul>
+			<li><a href="https://example.com" time_added="1500000000" tags="tag1,tag2">Example</a></li>
+			<li><a href="https://jeremydormitzer.com" time_added="1500000001" tags="">https://jeremydormitzer.com</a></li>
+		</ul>
+		<h1>Read Archive</h1>
+		<ul>
+			<li><a href="https://rust-lang.org" time_added="1500000002" tags="rust">Rust &mdash; a language</a></li>
+		</ul>
+	</body>
+</html>"#;
+
+    let links = pocket::parse(export).unwrap();
+    assert_eq!(
+        links,
+        vec![
+            LinkEntry::builder()
+                .set_url("https://example.com")
+                .set_title("Example")
+                .add_tags(&mut vec!["tag1", "tag2"])
+                .set_added(1500000000)
+                .build()
+                .unwrap(),
+            LinkEntry::builder()
+                .set_url("https://jeremydormitzer.com")
+                .set_title("https://jeremydormitzer.com")
+                .set_added(1500000001)
+                .build()
+                .unwrap(),
+            LinkEntry::builder()
+                .set_url("https://rust-lang.org")
+                .set_title("Rust \u{2014} a language")
+                .add_tags(&mut vec!["rust"])
+                .set_added(1500000002)
+                .set_read(true)
+                .build()
+                .unwrap(),
+        ]
+    );
+
+    let list = ReadLaterList::new().add_links(links);
+    assert!(list.to_string().ends_with(
+        "\
+url: https://rust-lang.org
+title: Rust \u{2014} a language
+tags: rust
+added: 1500000002
+read: true"
+    ));
+}
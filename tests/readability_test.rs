@@ -0,0 +1,64 @@
+extern crate open_read_later;
+
+use open_read_later::readability;
+
+const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <title>Understanding Ownership in Rust | Example Blog</title>
+  <script>var tracking = "<p>not content</p>";</script>
+</head>
+<body>
+  <header class="site-header"><a href="/">Example Blog</a> <a href="/about">About</a></header>
+  <nav><ul><li><a href="/">Home</a></li><li><a href="/archive">Archive</a></li></ul></nav>
+  <div id="main">
+    <article class="post">
+      <h1>Understanding Ownership in Rust</h1>
+      <p class="byline">By <a href="/jane" rel="author">Jane Doe</a></p>
+      <p>Ownership is Rust's most unique feature, and it enables Rust to make memory safety
+         guarantees without needing a garbage collector.</p>
+      <p>In this post, we'll talk about ownership as well as several related features:
+         borrowing, slices, and how Rust lays data out in memory.<br>Let's begin.</p>
+      <div class="share-buttons"><a href="https://twitter.com/share">Tweet this</a></div>
+      <p>Each value in Rust has a variable that's called its <em>owner</em>. There can only
+         be one owner at a time, and when the owner goes out of scope, the value is dropped.
+    </article>
+    <aside class="sidebar"><h2>Popular posts</h2><p>Something else entirely, with commas,
+      and more commas, and even more commas, to tempt the scorer.</p></aside>
+    <div class="comments"><p>Great post, thanks for writing it! I learned a lot, really.</p></div>
+  </div>
+  <footer>Copyright 2017, Example Blog. All rights reserved, forever and ever.</footer>
+</body>
+</html>"#;
+
+#[test]
+fn it_extracts_readable_articles() {
+    let article = readability::extract(PAGE, "https://example.com/ownership");
+    assert_eq!(article.title, Some(String::from("Understanding Ownership in Rust")));
+    assert_eq!(article.byline, Some(String::from("By Jane Doe")));
+    assert!(!article.content.contains("<h1>"));
+    assert!(article.content.contains("<em>owner</em>"));
+    assert!(article.content.contains("slices, and how Rust lays data out in memory.<br/>Let"));
+    assert_eq!(
+        article.text,
+        "Ownership is Rust's most unique feature, and it enables Rust to make memory safety \
+         guarantees without needing a garbage collector.\n\n\
+         In this post, we'll talk about ownership as well as several related features: \
+         borrowing, slices, and how Rust lays data out in memory. Let's begin.\n\n\
+         Each value in Rust has a variable that's called its owner. There can only be one \
+         owner at a time, and when the owner goes out of scope, the value is dropped."
+    );
+    assert_eq!(article.word_count, 77);
+}
+
+#[test]
+fn it_falls_back_to_the_page_body() {
+    let article = readability::extract(
+        "<title>Short - Site</title><body><p>Just a line.</p></body>",
+        "https://example.com/short",
+    );
+    assert_eq!(article.title, Some(String::from("Short - Site")));
+    assert_eq!(article.byline, None);
+    assert_eq!(article.text, "Just a line.");
+    assert_eq!(article.word_count, 3);
+}
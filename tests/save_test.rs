@@ -0,0 +1,58 @@
+extern crate open_read_later;
+
+mod common;
+
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[test]
+fn it_stamps_saved_links_with_the_time_they_were_added() {
+    let dir = common::temp_dir("save_test");
+    let list_file = dir.join("list");
+    fs::write(&list_file, "").unwrap();
+    let before = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    common::readlater(
+        &list_file,
+        &["save", "https://example.com", "--title", "Example", "--tags", "rust", "--offline"],
+    );
+    let list = common::read_list(&list_file);
+    let added = list.get_link("https://example.com").unwrap().added.unwrap();
+    assert!(added >= before && added <= before + 60);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+/// An article page of 450 words, which take 3 minutes to read.
+fn article_page() -> String {
+    let paragraph = format!("<p>{}</p>", vec!["word"; 45].join(" "));
+    format!(
+        "<html><head><title>Long read</title></head><body><article>{}</article></body></html>",
+        vec![paragraph; 10].join("\n")
+    )
+}
+
+#[test]
+fn it_records_the_reading_time_of_saved_links() {
+    let base = common::serve(|_, _| {
+        common::response("200 OK", "Content-Type: text/html\r\n", &article_page())
+    });
+    let dir = common::temp_dir("save_reading_time_test");
+    let list_file = dir.join("list");
+    fs::write(&list_file, "").unwrap();
+    let saved = format!("{}/saved", base);
+    let archived = format!("{}/archived", base);
+    common::readlater(&list_file, &["save", &saved, "--title", "Saved", "--tags", "rust"]);
+    common::readlater(
+        &list_file,
+        &["save", &archived, "--title", "Archived", "--tags", "rust", "--offline"],
+    );
+    assert_eq!(common::read_list(&list_file).get_link(&archived).unwrap().word_count, None);
+    common::readlater(&list_file, &["archive-content", &archived]);
+
+    let list = common::read_list(&list_file);
+    for url in &[saved, archived] {
+        let link = list.get_link(url).unwrap();
+        assert_eq!(link.word_count, Some(450));
+        assert_eq!(link.reading_minutes, Some(3));
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,36 @@
+extern crate open_read_later;
+
+use open_read_later::formats::site;
+use open_read_later::read_later_list::ReadLaterList;
+
+#[test]
+fn it_renders_static_sites() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://example.com
+title: <script>alert(1)</script>
+tags: C++, c
+---
+url: https://rust-lang.org
+title: Rust
+tags: c",
+    ).unwrap();
+
+    let pages = site::render(&list, "Reading", "<title>{{title}}</title>\n{{content}}");
+    let paths = pages
+        .iter()
+        .map(|page| page.path.as_ref())
+        .collect::<Vec<&str>>();
+    assert_eq!(paths, vec!["index.html", "tags/c.html", "tags/c-2.html"]);
+
+    assert!(pages[0].contents.starts_with("<title>Reading</title>"));
+    assert!(pages[0].contents.contains(
+        "    <li><a href=\"https://example.com\">&lt;script&gt;alert(1)&lt;/script&gt;</a>\
+         <span class=\"tags\"><a href=\"tags/c.html\">C++</a><a href=\"tags/c-2.html\">c</a></span></li>"
+    ));
+
+    assert!(pages[1].contents.starts_with("<title>Reading: C++</title>"));
+    assert!(pages[1].contents.contains("<a href=\"../tags/c-2.html\">c</a>"));
+    assert!(!pages[1].contents.contains("rust-lang.org"));
+    assert!(pages[2].contents.contains("rust-lang.org"));
+}
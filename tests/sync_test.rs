@@ -0,0 +1,47 @@
+extern crate open_read_later;
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use common::readlater;
+
+fn urls(list_file: &Path) -> Vec<String> {
+    let list = common::read_list(list_file);
+    let mut urls = list.iter_links().map(|link| link.url.clone()).collect::<Vec<String>>();
+    urls.sort();
+    urls
+}
+
+#[test]
+fn it_syncs_lists_through_a_shared_state_file() {
+    let dir = common::temp_dir("sync_test");
+    let laptop = dir.join("laptop_list");
+    let desktop = dir.join("desktop_list");
+    let shared = dir.join("shared.json");
+    let shared = shared.to_str().unwrap();
+    fs::write(&laptop, "url: https://example.com\ntitle: Example").unwrap();
+    fs::write(&desktop, "url: https://rust-lang.org\ntitle: Rust").unwrap();
+
+    readlater(&laptop, &["sync", shared, "--replica", "laptop"]);
+    readlater(&desktop, &["sync", shared, "--replica", "desktop"]);
+    assert_eq!(urls(&desktop), vec!["https://example.com", "https://rust-lang.org"]);
+
+    readlater(&desktop, &["delete", "https://example.com"]);
+    readlater(&desktop, &["sync", shared]);
+    readlater(&laptop, &["sync", shared]);
+    assert_eq!(urls(&laptop), vec!["https://rust-lang.org"]);
+
+    // A second device can't reuse a replica ID
+    let phone = dir.join("phone_list");
+    fs::write(&phone, "url: https://jeremydormitzer.com\ntitle: Jeremy").unwrap();
+    readlater(&phone, &["sync", shared, "--replica", "phone"]);
+    let impostor = dir.join("impostor_list");
+    fs::write(&impostor, "url: https://example.net\ntitle: Another").unwrap();
+    let output = readlater(&impostor, &["sync", shared, "--replica", "phone"]);
+    assert!(output.contains("another device that uses the replica ID phone"));
+    assert_eq!(urls(&impostor), vec!["https://example.net"]);
+    readlater(&phone, &["sync", shared]);
+    assert_eq!(urls(&phone), vec!["https://jeremydormitzer.com", "https://rust-lang.org"]);
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,94 @@
+extern crate open_read_later;
+
+use open_read_later::formats::todotxt;
+use open_read_later::read_later_list::ReadLaterList;
+
+#[test]
+fn it_renders_unread_links_as_todo_txt() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://example.com/a
+title: Async Rust
+tags: rust, type systems
+added: 1500000000
+---
+url: https://example.com/b
+title: Already read
+read: true
+---
+url: https://example.com/c
+title: https://example.com/c",
+    ).unwrap();
+    assert_eq!(
+        todotxt::render(&list),
+        "\
+2017-07-14 Async Rust https://example.com/a +rust +type-systems
+https://example.com/c
+"
+    );
+}
+
+#[test]
+fn it_parses_todo_txt_tasks_with_urls() {
+    let links = todotxt::parse(
+        "\
+(A) 2017-07-14 Read https://example.com/a about async +rust @laptop
+x 2017-07-20 2017-07-01 https://example.com/b +done-reading
+Call mom +family
+x Finished https://example.com/c
+",
+    ).unwrap();
+    assert_eq!(links.len(), 3);
+    assert_eq!(links[0].url, "https://example.com/a");
+    assert_eq!(links[0].title, "Read about async @laptop");
+    assert_eq!(links[0].tags, vec!["rust"]);
+    assert_eq!(links[0].added, Some(1499990400));
+    assert!(!links[0].read);
+    assert_eq!(links[1].title, "https://example.com/b");
+    assert_eq!(links[1].added, Some(1498867200));
+    assert!(links[1].read);
+    assert_eq!(links[2].title, "Finished");
+    assert!(links[2].read);
+}
+
+#[test]
+fn it_keeps_titles_that_look_like_todo_txt_syntax() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://example.com/a
+title: x marks the spot
+---
+url: https://example.com/b
+title: (A) Priority reading
+---
+url: https://example.com/c
+title: 2017-07-14 release notes
+added: 1500000000
+---
+url: https://example.com/d
+title: Why +1 comments are noise",
+    ).unwrap();
+    let text = todotxt::render(&list);
+    assert_eq!(
+        text,
+        "\
+https://example.com/a x marks the spot
+https://example.com/b (A) Priority reading
+2017-07-14 https://example.com/c 2017-07-14 release notes
+Why ＋1 comments are noise https://example.com/d
+"
+    );
+    let links = todotxt::parse(&text).unwrap();
+    let titles = links.iter().map(|link| link.title.as_str()).collect::<Vec<&str>>();
+    assert_eq!(
+        titles,
+        vec![
+            "x marks the spot",
+            "(A) Priority reading",
+            "2017-07-14 release notes",
+            "Why ＋1 comments are noise",
+        ]
+    );
+    assert!(links.iter().all(|link| !link.read && link.tags.is_empty()));
+    assert_eq!(links[2].added, Some(1499990400));
+}
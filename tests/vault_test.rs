@@ -0,0 +1,120 @@
+extern crate open_read_later;
+
+mod common;
+
+use std::fs;
+use open_read_later::formats::vault;
+use open_read_later::read_later_list::ReadLaterList;
+
+#[test]
+fn it_renders_vault_notes() {
+    let list = ReadLaterList::parse(
+        "\
+url: https://example.com/posts/async-rust?page=2
+title: Async \"Rust\" explained
+tags: rust, async
+added: 1500000000
+---
+url: https://example.com
+title: Example
+read: true",
+    ).unwrap();
+
+    let notes = vault::render(&list);
+    assert_eq!(notes.len(), 2);
+    assert!(notes[0].path.starts_with("example-com-"));
+    assert!(notes[1].path.starts_with("example-com-posts-async-rust-page-2-"));
+    assert!(notes[1].path.ends_with(".md"));
+    let hash = vault::note_hash(&notes[1].contents).unwrap();
+    assert_eq!(hash.len(), 8);
+    assert_eq!(
+        notes[1].contents,
+        format!(
+            "\
+---
+url: \"https://example.com/posts/async-rust?page=2\"
+title: \"Async \\\"Rust\\\" explained\"
+tags:
+  - \"rust\"
+  - \"async\"
+added: 2017-07-14T02:40:00Z
+read: false
+readlater_hash: \"{}\"
+---
+
+# Async \"Rust\" explained
+
+<https://example.com/posts/async-rust?page=2>
+",
+            hash
+        )
+    );
+    assert!(notes[0].contents.contains("tags: []\nread: true\n"));
+}
+
+#[test]
+fn it_keeps_vault_file_names_stable() {
+    let before = ReadLaterList::parse("url: https://example.com\ntitle: Example").unwrap();
+    let after = ReadLaterList::parse("url: https://example.com\ntitle: Renamed\ntags: new").unwrap();
+    let other = ReadLaterList::parse("url: http://example.com\ntitle: Example").unwrap();
+    let path = |list: &ReadLaterList| vault::render(list)[0].path.clone();
+    assert_eq!(path(&before), path(&after));
+    assert!(path(&before) != path(&other));
+}
+
+#[test]
+fn it_keeps_edited_note_bodies() {
+    let before = ReadLaterList::parse("url: https://example.com\ntitle: Example").unwrap();
+    let after = ReadLaterList::parse("url: https://example.com\ntitle: Renamed").unwrap();
+    let note = vault::render(&before)[0].contents.clone();
+    let edited = format!("{}\nMy notes on this page.\n", note);
+    assert_eq!(vault::update_note(&edited, &note), None);
+
+    let updated = vault::update_note(&edited, &vault::render(&after)[0].contents).unwrap();
+    assert!(updated.contains("title: \"Renamed\"\n"));
+    assert!(updated.ends_with("\n# Example\n\n<https://example.com>\n\nMy notes on this page.\n"));
+    assert_eq!(vault::update_note(&updated, &vault::render(&after)[0].contents), None);
+
+    // A file without front matter gets it added above its text
+    let updated = vault::update_note("Just text\n", &note).unwrap();
+    assert!(updated.starts_with("---\nurl: \"https://example.com\"\n"));
+    assert!(updated.ends_with("---\n\nJust text\n"));
+}
+
+#[test]
+fn it_exports_vaults_incrementally() {
+    let dir = common::temp_dir("vault_test");
+    let vault_dir = dir.join("vault");
+    let list_file = dir.join("list");
+    let export = |extra: &[&str]| {
+        let mut args = vec!["export", "--format", "vault", "--out", vault_dir.to_str().unwrap()];
+        args.extend_from_slice(extra);
+        common::readlater(&list_file, &args)
+    };
+    fs::write(
+        &list_file,
+        "url: https://example.com\ntitle: Example\n---\nurl: https://example.org\ntitle: Other",
+    ).unwrap();
+    export(&[]);
+    let list = common::read_list(&list_file);
+    let notes = vault::render(&list);
+    let kept = vault_dir.join(&notes[0].path);
+    let removed = vault_dir.join(&notes[1].path);
+    let edited = format!("{}\nMy notes on this page.\n", fs::read_to_string(&kept).unwrap());
+    fs::write(&kept, &edited).unwrap();
+
+    fs::write(&list_file, "url: https://example.com\ntitle: Example\ntags: rust").unwrap();
+    let output = export(&["--incremental"]);
+    assert!(output.contains("Wrote 1 notes"));
+    assert!(output.contains("is for a link that is no longer in the list"));
+    let note = fs::read_to_string(&kept).unwrap();
+    assert!(note.contains("tags:\n  - \"rust\"\n"));
+    assert!(note.ends_with("\nMy notes on this page.\n"));
+    assert!(removed.exists());
+
+    let output = export(&["--incremental", "--prune"]);
+    assert!(output.contains("Wrote 0 notes"));
+    assert!(!removed.exists());
+    assert!(fs::read_to_string(&kept).unwrap().ends_with("\nMy notes on this page.\n"));
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,67 @@
+extern crate open_read_later;
+
+mod common;
+
+use std::fs;
+use std::sync::{Arc, Mutex};
+use common::readlater;
+
+/// Serves whatever page is in `page` on a local port, returning the URL.
+fn serve_page(page: Arc<Mutex<String>>) -> String {
+    let base = common::serve(move |_, _| {
+        common::response("200 OK", "Content-Type: text/html\r\n", &page.lock().unwrap())
+    });
+    format!("{}/article", base)
+}
+
+fn article_page(paragraphs: &[&str]) -> String {
+    let paragraphs = paragraphs
+        .iter()
+        .map(|text| format!("<p>{}</p>", text))
+        .collect::<Vec<String>>()
+        .join("\n");
+    format!(
+        "<html><head><title>Article</title></head><body><article>\n{}\n</article></body></html>",
+        paragraphs
+    )
+}
+
+#[test]
+fn it_reports_and_tags_changed_pages() {
+    let page = Arc::new(Mutex::new(article_page(&[
+        "The first paragraph of the article, which will stay the same over time.",
+        "The second paragraph of the article, which is about to be rewritten.",
+    ])));
+    let url = serve_page(page.clone());
+    let dir = common::temp_dir("watch_test");
+    let list_file = dir.join("list");
+    fs::write(&list_file, format!("url: {}\ntitle: Article", url)).unwrap();
+    readlater(&list_file, &["archive-content", &url]);
+
+    let output = readlater(&list_file, &["watch-changes"]);
+    assert!(output.contains("Checked 1 archived links: 0 changed"));
+
+    *page.lock().unwrap() = article_page(&[
+        "The first paragraph of the article, which will stay the same over time.",
+        "The second paragraph of the article, now that it has been rewritten.",
+    ]);
+    let output = readlater(&list_file, &["watch-changes", "--update"]);
+    assert!(output.contains(&format!("Changed: {}\n", url)));
+    assert!(output.contains(
+        "\n-The second paragraph of the article, which is about to be rewritten."
+    ));
+    assert!(output.contains(
+        "\n+The second paragraph of the article, now that it has been rewritten."
+    ));
+    assert!(output.contains("Checked 1 archived links: 1 changed"));
+    let tags = || {
+        common::read_list(&list_file).get_link(&url).unwrap().tags.clone()
+    };
+    assert_eq!(tags(), vec!["changed"]);
+
+    // The updated snapshot is the new baseline, but the tag stays until it is removed
+    let output = readlater(&list_file, &["watch-changes"]);
+    assert!(output.contains("Checked 1 archived links: 0 changed"));
+    assert_eq!(tags(), vec!["changed"]);
+    fs::remove_dir_all(dir).unwrap();
+}